@@ -15,3 +15,19 @@ pub fn error_logger(string: String) {
 pub fn success_logger(string: String) {
     println!("{}{}{}", GREEN, string, RESET);
 }
+
+/// Looks up a color by the name used in the theme of the config, `none` means no color
+pub fn from_name(name: &str) -> Option<&'static str> {
+    match name {
+        "none" => Some(""),
+        "black" => Some("\x1b[30m"),
+        "red" => Some(RED),
+        "green" => Some(GREEN),
+        "yellow" => Some(ANSI_COLOR_YELLOW),
+        "blue" => Some(ANSI_COLOR_BLUE),
+        "magenta" => Some(ANSI_COLOR_MAGENTA),
+        "cyan" => Some(ANSI_COLOR_CYAN),
+        "white" => Some("\x1b[37m"),
+        _ => None,
+    }
+}
@@ -0,0 +1,271 @@
+use unicode_width::UnicodeWidthChar;
+
+use crate::cli::colors;
+use crate::config::Theme;
+use crate::error::{ErrorKind, ShellError};
+use crate::parser::span::Span;
+
+const TAB_WIDTH: usize = 4;
+
+/// Renders an error and its causes like a compiler diagnostic:
+///
+/// ```text
+/// error: where needs positional parameter: condition
+///  --> 1:6
+///   |
+/// 1 | ls | where
+///   |      ^^^^^
+///   = help: run `help where` to see its parameters
+/// ```
+///
+/// `source` is the line that was run, the spans of an error raised while running some other text,
+/// like the body of a `def`, point into the source carried by the error and its causes.
+pub fn render(err: &ShellError, source: &str, theme: &Theme) -> String {
+    let mut out = String::new();
+    let mut source = source;
+    for (i, err) in err.chain().enumerate() {
+        if let Some(own) = &err.details.source {
+            source = own;
+        }
+        let title = if i == 0 { "error" } else { "caused by" };
+        render_one(&mut out, err, title, source, theme);
+    }
+    out
+}
+
+struct Label<'a> {
+    location: Location<'a>,
+    message: &'a str,
+    primary: bool,
+}
+
+/// Where a span is, the columns count the display width of the text before it
+struct Location<'a> {
+    line: usize,
+    text: &'a str,
+    column: usize,
+    width: usize,
+}
+
+fn render_one(out: &mut String, err: &ShellError, title: &str, source: &str, theme: &Theme) {
+    // the kind is shown like an error code, so scripts and users can tell errors apart
+    let kind = match err.kind() {
+        Some(kind) if kind != ErrorKind::Other => format!("[{}]", kind.name()),
+        _ => String::new(),
+    };
+    out.push_str(&format!(
+        "{}{}{}: {}{}\n",
+        theme.error(),
+        title,
+        kind,
+        err.error,
+        colors::RESET
+    ));
+    let mut labels: Vec<Label<'_>> = err
+        .span()
+        .into_iter()
+        .map(|span| (span, "", true))
+        .chain(
+            err.details
+                .labels
+                .iter()
+                .map(|label| (label.span, label.message.as_str(), false)),
+        )
+        .filter_map(|(span, message, primary)| {
+            locate(source, span).map(|location| Label {
+                location,
+                message,
+                primary,
+            })
+        })
+        .collect();
+    // the primary label comes first so it gives the position in the header
+    let last_line = labels.iter().map(|l| l.location.line).max().unwrap_or(0);
+    let gutter = " ".repeat((last_line + 1).to_string().len());
+    if let Some(first) = labels.first() {
+        out.push_str(&format!(
+            "{}--> {}:{}\n",
+            gutter,
+            first.location.line + 1,
+            first.location.column + 1
+        ));
+        out.push_str(&format!("{} |\n", gutter));
+        labels.sort_by_key(|label| (label.location.line, !label.primary));
+        let mut previous_line = None;
+        for label in labels.iter() {
+            let line = label.location.line;
+            if previous_line != Some(line) {
+                if previous_line.is_some_and(|previous| previous + 1 < line) {
+                    out.push_str(&format!("{}...\n", gutter));
+                }
+                out.push_str(&format!(
+                    "{:>width$} | {}\n",
+                    line + 1,
+                    expand_tabs(label.location.text),
+                    width = gutter.len()
+                ));
+                previous_line = Some(line);
+            }
+            let (color, marker) = if label.primary {
+                (theme.error(), "^")
+            } else {
+                (colors::ANSI_COLOR_CYAN, "-")
+            };
+            let underline = format!(
+                "{}{}{}",
+                " ".repeat(label.location.column),
+                marker.repeat(label.location.width),
+                if label.message.is_empty() {
+                    String::new()
+                } else {
+                    format!(" {}", label.message)
+                }
+            );
+            out.push_str(&format!(
+                "{} | {}{}{}\n",
+                gutter,
+                color,
+                underline,
+                colors::RESET
+            ));
+        }
+    }
+    if let Some(help) = &err.details.help {
+        out.push_str(&format!("{} = help: {}\n", gutter, help));
+    }
+    for note in err.details.notes.iter() {
+        out.push_str(&format!("{} = note: {}\n", gutter, note));
+    }
+}
+
+/// Maps a span to its line and columns, a span over several lines is cut at the end of the first.
+/// Returns `None` if the span doesn't point into the source.
+fn locate(source: &str, span: Span) -> Option<Location<'_>> {
+    let start = span.start();
+    if span.is_unknown() || start > source.len() || !source.is_char_boundary(start) {
+        return None;
+    }
+    let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = source[start..]
+        .find('\n')
+        .map_or(source.len(), |i| start + i);
+    let end = span.end().min(line_end);
+    if !source.is_char_boundary(end) {
+        return None;
+    }
+    Some(Location {
+        line: source[..line_start].matches('\n').count(),
+        text: source[line_start..line_end].trim_end_matches('\r'),
+        column: display_width(&source[line_start..start]),
+        width: display_width(&source[start..end]).max(1),
+    })
+}
+
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| {
+            if c == '\t' {
+                TAB_WIDTH
+            } else {
+                c.width().unwrap_or(0)
+            }
+        })
+        .sum()
+}
+
+fn expand_tabs(text: &str) -> String {
+    text.replace('\t', &" ".repeat(TAB_WIDTH))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render;
+    use crate::config::Theme;
+    use crate::error::{ErrorKind, ShellError};
+    use crate::parser::span::Span;
+
+    /// Renders an error at the span of the source, without the colors
+    fn rendered(source: &str, start: usize, end: usize) -> String {
+        let err =
+            ShellError::new(ErrorKind::NotFound, "Unknown variable: $x").at(Span::new(start, end));
+        let out = render(&err, source, &Theme::default());
+        let mut plain = String::new();
+        let mut chars = out.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                chars.by_ref().find(|&c| c == 'm');
+            } else {
+                plain.push(c);
+            }
+        }
+        plain
+    }
+
+    #[test]
+    fn span_on_the_first_line() {
+        assert_eq!(
+            rendered("echo $x", 5, 7),
+            "error[not-found]: Unknown variable: $x\n \
+             --> 1:6\n  \
+             |\n\
+             1 | echo $x\n  \
+             |      ^^\n"
+        );
+    }
+
+    #[test]
+    fn span_on_a_later_line() {
+        let source = "ls\npwd\necho $x\nexit";
+        let start = source.find('$').unwrap();
+        assert_eq!(
+            rendered(source, start, start + 2),
+            "error[not-found]: Unknown variable: $x\n \
+             --> 3:6\n  \
+             |\n\
+             3 | echo $x\n  \
+             |      ^^\n"
+        );
+    }
+
+    #[test]
+    fn gutter_grows_with_the_line_number() {
+        let source = "ls\n".repeat(9) + "echo $x";
+        let start = source.find('$').unwrap();
+        assert!(rendered(&source, start, start + 2)
+            .contains("  --> 10:6\n   |\n10 | echo $x\n   |      ^^\n"));
+    }
+
+    #[test]
+    fn columns_count_the_display_width_of_wide_characters() {
+        // the two characters take six bytes and four columns
+        let source = "echo 中文 $x";
+        let start = source.find('$').unwrap();
+        assert_eq!(start, 12);
+        assert!(rendered(source, start, start + 2).contains(" --> 1:11\n"));
+        assert!(rendered(source, start, start + 2).ends_with(&format!("|{}^^\n", " ".repeat(11))));
+        // a span over them is underlined by their width
+        assert!(rendered(source, 5, 11).ends_with(&format!("|{}^^^^\n", " ".repeat(6))));
+    }
+
+    #[test]
+    fn span_at_the_end_of_the_source() {
+        // eg the missing argument of `ls | where`
+        let source = "ls | where";
+        assert_eq!(
+            rendered(source, source.len(), source.len()),
+            "error[not-found]: Unknown variable: $x\n \
+             --> 1:11\n  \
+             |\n\
+             1 | ls | where\n  \
+             |           ^\n"
+        );
+    }
+
+    #[test]
+    fn span_outside_of_the_source_is_left_out() {
+        assert_eq!(
+            rendered("ls", 10, 12),
+            "error[not-found]: Unknown variable: $x\n"
+        );
+    }
+}
@@ -0,0 +1,252 @@
+use std::borrow::Cow;
+
+use rustyline::completion::{Completer, Pair};
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+
+use crate::cli::colors;
+use crate::context::{Aliases, CommandRegistry};
+use crate::path_cache::PathCache;
+use crate::shell::ShellManager;
+
+/// The rustyline helper, it completes command names in command position, the flags of the
+/// current command after `--`, and file names in the directory of the current shell everywhere else.
+/// It also shows the commands that aren't found in the error color as they are typed.
+pub struct Helper {
+    registry: CommandRegistry,
+    aliases: Aliases,
+    path_cache: PathCache,
+    shell: ShellManager,
+    /// The prompt with the colors of the theme, shown in place of the plain prompt
+    pub colored_prompt: String,
+    /// The color of the theme for commands that aren't found
+    pub error_color: &'static str,
+}
+
+impl Helper {
+    pub fn new(
+        registry: CommandRegistry,
+        aliases: Aliases,
+        path_cache: PathCache,
+        shell: ShellManager,
+    ) -> Helper {
+        Helper {
+            registry,
+            aliases,
+            path_cache,
+            shell,
+            colored_prompt: String::new(),
+            error_color: "",
+        }
+    }
+
+    /// Whether a word in command position runs something
+    fn is_command(&self, word: &str) -> bool {
+        self.registry.has(word)
+            || self.aliases.get(word).is_some()
+            || self.path_cache.resolve(word, &self.shell.cwd()).is_some()
+    }
+
+    /// The entries of the directory the word points into whose names start like its last part,
+    /// directories end with a separator so that completion can go on inside them
+    fn complete_path(&self, word: &str) -> Vec<Pair> {
+        let (dir, prefix) = match word.rfind(std::path::is_separator) {
+            Some(i) => word.split_at(i + 1),
+            None => ("", word),
+        };
+        let base = self.shell.cwd().join(shellexpand::tilde(dir).as_ref());
+        let entries = match base.read_dir() {
+            Ok(entries) => entries,
+            Err(_) => return vec![],
+        };
+        let mut candidates = entries
+            .filter_map(Result::ok)
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.'))
+                {
+                    return None;
+                }
+                let mut display = name;
+                if entry.path().is_dir() {
+                    display.push(std::path::MAIN_SEPARATOR);
+                }
+                let path = format!("{}{}", dir, display);
+                let replacement = if path.contains(char::is_whitespace) {
+                    format!("\"{}\"", path)
+                } else {
+                    path
+                };
+                Some(Pair {
+                    display,
+                    replacement,
+                })
+            })
+            .collect::<Vec<_>>();
+        candidates.sort_by(|a, b| a.display.cmp(&b.display));
+        candidates
+    }
+}
+
+impl Completer for Helper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &rustyline::Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let before = &line[..pos];
+        // the current pipeline element starts after the last pipe or opening brace
+        let element_start = before.rfind(&['|', '{'][..]).map(|i| i + 1).unwrap_or(0);
+        let element = &before[element_start..];
+        let word_start = element
+            .rfind(char::is_whitespace)
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        let word = &element[word_start..];
+        let start = element_start + word_start;
+
+        if element[..word_start].trim().is_empty() {
+            let mut names = self.registry.names();
+            names.extend(self.aliases.list().into_iter().map(|(name, _)| name));
+            names.extend(self.path_cache.names());
+            names.sort();
+            names.dedup();
+            let candidates = names
+                .into_iter()
+                .filter(|name| name.starts_with(word))
+                .map(|name| Pair {
+                    display: name.clone(),
+                    replacement: name,
+                })
+                .collect::<Vec<_>>();
+            if !candidates.is_empty() {
+                return Ok((start, candidates));
+            }
+        } else if word.starts_with("--") {
+            let command = element.split_whitespace().next().unwrap_or_default();
+            if let Some(signature) = self.registry.get(command) {
+                let candidates = signature
+                    .named
+                    .keys()
+                    .map(|flag| format!("--{}", flag))
+                    .filter(|flag| flag.starts_with(word))
+                    .map(|flag| Pair {
+                        display: flag.clone(),
+                        replacement: flag,
+                    })
+                    .collect();
+                return Ok((start, candidates));
+            }
+        }
+        Ok((start, self.complete_path(word)))
+    }
+}
+
+impl Hinter for Helper {}
+
+impl Highlighter for Helper {
+    fn highlight_prompt<'b, 's: 'b, 'p: 'b>(
+        &'s self,
+        prompt: &'p str,
+        default: bool,
+    ) -> Cow<'b, str> {
+        if default && !self.colored_prompt.is_empty() {
+            Cow::Borrowed(&self.colored_prompt)
+        } else {
+            Cow::Borrowed(prompt)
+        }
+    }
+
+    fn highlight<'l>(&self, line: &'l str, _pos: usize) -> Cow<'l, str> {
+        if self.error_color.is_empty() {
+            return Cow::Borrowed(line);
+        }
+        let unknown = command_words(line)
+            .into_iter()
+            .filter(|&(start, end)| !self.is_command(&line[start..end]))
+            .collect::<Vec<_>>();
+        if unknown.is_empty() {
+            return Cow::Borrowed(line);
+        }
+        let mut highlighted = String::new();
+        let mut last = 0;
+        for (start, end) in unknown {
+            highlighted.push_str(&line[last..start]);
+            highlighted.push_str(self.error_color);
+            highlighted.push_str(&line[start..end]);
+            highlighted.push_str(colors::RESET);
+            last = end;
+        }
+        highlighted.push_str(&line[last..]);
+        Cow::Owned(highlighted)
+    }
+
+    fn highlight_char(&self, _line: &str, _pos: usize) -> bool {
+        true
+    }
+}
+
+/// The start and end of the words in command position: at the start of the line, after a pipe or
+/// a `;`, and at the start of a block after its parameters like in `{|x| echo $x}`. Words in
+/// quotes are skipped, and braces with alternatives like `{a,b}` are part of a word.
+fn command_words(line: &str) -> Vec<(usize, usize)> {
+    let mut words = vec![];
+    let mut command_position = true;
+    let mut block_start = false;
+    let mut quote = None;
+    let mut chars = line.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if let Some(open) = quote {
+            if c == open {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' | '`' => {
+                quote = Some(c);
+                command_position = false;
+                block_start = false;
+            }
+            '|' if block_start => {
+                // the parameters of the block
+                for (_, c) in chars.by_ref() {
+                    if c == '|' {
+                        break;
+                    }
+                }
+                block_start = false;
+            }
+            '|' | ';' | '\n' => command_position = true,
+            '{' if matches!(chars.peek(), None | Some((_, ' ')) | Some((_, '|'))) => {
+                command_position = true;
+                block_start = true;
+            }
+            c if c.is_whitespace() => {}
+            _ => {
+                let mut end = line.len();
+                while let Some(&(index, c)) = chars.peek() {
+                    if c.is_whitespace() || "|;}\"'`".contains(c) {
+                        end = index;
+                        break;
+                    }
+                    chars.next();
+                }
+                if command_position && c != '}' {
+                    words.push((start, end));
+                }
+                command_position = false;
+                block_start = false;
+            }
+        }
+    }
+    words
+}
+
+impl Validator for Helper {}
+
+impl rustyline::Helper for Helper {}
@@ -0,0 +1,143 @@
+use rustyline::config::{BellStyle, CompletionType};
+use rustyline::{Anchor, At, Cmd, EditMode, KeyPress, Movement, Word};
+
+/// Opens the marker inserted by a `command:` binding, the pipeline up to the closing marker is
+/// replaced by its output when the line is accepted
+pub const COMMAND_START: char = '⟪';
+pub const COMMAND_END: char = '⟫';
+
+pub fn edit_mode(name: &str) -> Option<EditMode> {
+    match name {
+        "emacs" => Some(EditMode::Emacs),
+        "vi" => Some(EditMode::Vi),
+        _ => None,
+    }
+}
+
+pub fn bell_style(name: &str) -> Option<BellStyle> {
+    match name {
+        "audible" => Some(BellStyle::Audible),
+        "visible" => Some(BellStyle::Visible),
+        "none" => Some(BellStyle::None),
+        _ => None,
+    }
+}
+
+pub fn completion_type(name: &str) -> Option<CompletionType> {
+    match name {
+        "circular" => Some(CompletionType::Circular),
+        "list" => Some(CompletionType::List),
+        _ => None,
+    }
+}
+
+/// Parses a key like `ctrl-r`, `alt-f`, `ctrl-left`, `f5` or `x`
+pub fn parse_key(key: &str) -> Option<KeyPress> {
+    let lower = key.to_lowercase();
+    let single = |s: &str| {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(c),
+            _ => None,
+        }
+    };
+    if let Some(rest) = lower.strip_prefix("ctrl-") {
+        return match rest {
+            "left" => Some(KeyPress::ControlLeft),
+            "right" => Some(KeyPress::ControlRight),
+            "up" => Some(KeyPress::ControlUp),
+            "down" => Some(KeyPress::ControlDown),
+            "space" => Some(KeyPress::Ctrl(' ')),
+            _ => single(rest).map(|c| KeyPress::Ctrl(c.to_ascii_uppercase())),
+        };
+    }
+    if let Some(rest) = lower
+        .strip_prefix("alt-")
+        .or_else(|| lower.strip_prefix("meta-"))
+    {
+        return single(rest).map(KeyPress::Meta);
+    }
+    if let Some(rest) = lower.strip_prefix("shift-") {
+        return match rest {
+            "left" => Some(KeyPress::ShiftLeft),
+            "right" => Some(KeyPress::ShiftRight),
+            "up" => Some(KeyPress::ShiftUp),
+            "down" => Some(KeyPress::ShiftDown),
+            "tab" => Some(KeyPress::BackTab),
+            _ => None,
+        };
+    }
+    if lower.len() > 1 && lower.starts_with('f') {
+        if let Ok(n) = lower[1..].parse::<u8>() {
+            return Some(KeyPress::F(n));
+        }
+    }
+    match lower.as_str() {
+        "up" => Some(KeyPress::Up),
+        "down" => Some(KeyPress::Down),
+        "left" => Some(KeyPress::Left),
+        "right" => Some(KeyPress::Right),
+        "home" => Some(KeyPress::Home),
+        "end" => Some(KeyPress::End),
+        "pageup" => Some(KeyPress::PageUp),
+        "pagedown" => Some(KeyPress::PageDown),
+        "insert" => Some(KeyPress::Insert),
+        "delete" => Some(KeyPress::Delete),
+        "backspace" => Some(KeyPress::Backspace),
+        "tab" => Some(KeyPress::Tab),
+        "esc" => Some(KeyPress::Esc),
+        "enter" => Some(KeyPress::Enter),
+        _ => single(key).map(KeyPress::Char),
+    }
+}
+
+/// Parses the action of a key binding: the readline name of a command like `backward-word`,
+/// `insert:<text>` to insert some text, or `command:<pipeline>` to insert the output of a pipeline
+pub fn parse_cmd(action: &str) -> Option<Cmd> {
+    if let Some(text) = action.strip_prefix("insert:") {
+        return Some(Cmd::Insert(1, text.to_string()));
+    }
+    if let Some(pipeline) = action.strip_prefix("command:") {
+        return Some(Cmd::Insert(
+            1,
+            format!("{}{}{}", COMMAND_START, pipeline.trim(), COMMAND_END),
+        ));
+    }
+    let cmd = match action {
+        "accept-line" => Cmd::AcceptLine,
+        "backward-char" => Cmd::Move(Movement::BackwardChar(1)),
+        "forward-char" => Cmd::Move(Movement::ForwardChar(1)),
+        "backward-word" => Cmd::Move(Movement::BackwardWord(1, Word::Vi)),
+        "forward-word" => Cmd::Move(Movement::ForwardWord(1, At::AfterEnd, Word::Vi)),
+        "beginning-of-line" => Cmd::Move(Movement::BeginningOfLine),
+        "end-of-line" => Cmd::Move(Movement::EndOfLine),
+        "backward-delete-char" => Cmd::Kill(Movement::BackwardChar(1)),
+        "delete-char" => Cmd::Kill(Movement::ForwardChar(1)),
+        "kill-line" => Cmd::Kill(Movement::EndOfLine),
+        "backward-kill-line" => Cmd::Kill(Movement::BeginningOfLine),
+        "kill-whole-line" => Cmd::Kill(Movement::WholeLine),
+        "kill-word" => Cmd::Kill(Movement::ForwardWord(1, At::AfterEnd, Word::Emacs)),
+        "backward-kill-word" => Cmd::Kill(Movement::BackwardWord(1, Word::Emacs)),
+        "previous-history" => Cmd::PreviousHistory,
+        "next-history" => Cmd::NextHistory,
+        "beginning-of-history" => Cmd::BeginningOfHistory,
+        "end-of-history" => Cmd::EndOfHistory,
+        "reverse-search-history" => Cmd::ReverseSearchHistory,
+        "forward-search-history" => Cmd::ForwardSearchHistory,
+        "history-search-backward" => Cmd::HistorySearchBackward,
+        "history-search-forward" => Cmd::HistorySearchForward,
+        "clear-screen" => Cmd::ClearScreen,
+        "complete" => Cmd::Complete,
+        "transpose-chars" => Cmd::TransposeChars,
+        "transpose-words" => Cmd::TransposeWords(1),
+        "upcase-word" => Cmd::UpcaseWord,
+        "downcase-word" => Cmd::DowncaseWord,
+        "capitalize-word" => Cmd::CapitalizeWord,
+        "undo" => Cmd::Undo(1),
+        "yank" => Cmd::Yank(1, Anchor::Before),
+        "interrupt" => Cmd::Interrupt,
+        "noop" => Cmd::Noop,
+        _ => return None,
+    };
+    Some(cmd)
+}
@@ -1,80 +1,116 @@
-use rustyline::{self, At, Cmd, ColorMode, Config, Editor, KeyPress, Movement, Word};
+use rustyline::config::{CompletionType, Configurer};
+use rustyline::{self, ColorMode, EditMode, Editor, KeyPress};
 use rustyline::error::ReadlineError;
 use std::sync::Arc;
 use std::sync::atomic::Ordering;
 
-use crate::commands::{BoxedCommand, Command};
-use crate::commands::classified::pipeline::run_pipeline;
+use crate::commands::{output_items, BoxedCommand, Command};
+use crate::commands::classified::pipeline::{run_block, run_pipeline};
+use crate::config::{self, Config, Theme};
 use crate::context::Context;
 use crate::error::{ProximateShellError, ShellError};
+use crate::evaluate::block::Block;
+use crate::jobs;
 use crate::parser;
-use crate::parser::command::classified::{ClassifiedCommand, ClassifiedPipeline, Commands};
+use crate::parser::command::classified::{ClassifiedCommand, ClassifiedPipeline};
 use crate::parser::command::classified::external::{ExternalArgs, ExternalCommand};
 use crate::parser::command::classified::internal::InternalCommand;
 use crate::parser::command::parse_command_tail;
-use crate::parser::span::HasSpan;
+use crate::parser::span::{HasSpan, Span};
 use crate::parser::token::{SpannedToken, Token};
+use crate::shell::{MemoryShell, ShellManager};
 
 pub mod colors;
+pub mod diagnostic;
+pub mod helper;
+pub mod keybindings;
+pub mod table;
 
 pub fn cli() -> Result<(), ShellError> {
     let mut context = create_default_context();
-    let config = Config::builder().color_mode(ColorMode::Forced).build();
-    let mut rl: Editor<()> = Editor::with_config(config);
-    // add key bindings to move over a whole word with Ctrl+ArrowLeft and Ctrl+ArrowRight
-    rl.bind_sequence(
-        KeyPress::ControlLeft,
-        Cmd::Move(Movement::BackwardWord(1, Word::Vi)),
-    );
-    rl.bind_sequence(
-        KeyPress::ControlRight,
-        Cmd::Move(Movement::ForwardWord(1, At::AfterEnd, Word::Vi)),
-    );
-    let cc = context.ctrl_c.clone();
+    let dry_run = std::env::args().skip(1).any(|arg| arg == "--dry-run");
+    if dry_run {
+        context.shell = ShellManager::new(Box::new(MemoryShell::dry_run(context.shell.cwd())));
+    }
+    match Config::load() {
+        Ok(config) => *context.config.lock() = config,
+        Err(err) => report_error("", &err, &Theme::default()),
+    }
+    let config = context.config.lock().clone();
+    let editor_config = rustyline::Config::builder()
+        .color_mode(ColorMode::Forced)
+        .max_history_size(config.history_size)
+        .build();
+    let mut rl: Editor<helper::Helper> = Editor::with_config(editor_config);
+    let history_path = config::history_path();
+    if let Some(path) = &history_path {
+        let _ = rl.load_history(path);
+    }
+    rl.set_helper(Some(helper::Helper::new(
+        context.registry.clone(),
+        context.aliases.clone(),
+        context.path_cache.clone(),
+        context.shell.clone(),
+    )));
+    for err in config.invalid_keybindings() {
+        report_error("", &err, &config.theme);
+    }
+    let mut bound_keys = vec![];
+    let jobs = context.jobs.clone();
     ctrlc::set_handler(move || {
-        cc.store(true, Ordering::SeqCst);
+        jobs.interrupt();
     })
     .expect("Error setting Ctrl-C handler");
     #[cfg(windows)]
     {
         let _ = ansi_term::enable_ansi_support();
     }
+    jobs::init();
     let mut ctrlcbreak = false;
+    let mut initial: Option<String> = None;
     println!("Welcome to Li's shell, type help for more info.");
+    if dry_run {
+        println!("Dry run: changes to files are printed instead of made, external commands still run.");
+    }
+    run_rc_file(&mut context);
     loop {
-        if context.ctrl_c.load(Ordering::SeqCst) {
-            context.ctrl_c.store(false, Ordering::SeqCst);
-            continue;
+        for message in context.jobs.poll() {
+            println!("{}", message);
         }
-        let cwd = std::env::current_dir().expect("can't get current dir");
-        let colored_prompt = format!("\x1b[32m{}\x1b[0m> ", cwd.to_string_lossy().to_string());
-        let prompt = {
-            if let Ok(bytes) = strip_ansi_escapes::strip(&colored_prompt) {
-                String::from_utf8_lossy(&bytes).to_string()
-            } else {
-                "> ".to_string()
-            }
+        // `config set` may have changed the settings since the last line
+        let config = context.config.lock().clone();
+        apply_config(&mut rl, &config, &mut bound_keys);
+        let prompt = config.prompt(&context.shell.path(), &shell_label(&context));
+        if let Some(helper) = rl.helper_mut() {
+            helper.colored_prompt =
+                format!("{}{}{}", config.theme.prompt(), prompt, colors::RESET);
+            helper.error_color = config.theme.error();
+        }
+        let readline = match initial.take() {
+            Some(line) => rl.readline_with_initial(&prompt, (line.as_str(), "")),
+            None => rl.readline(&prompt),
         };
-        //        let prompt = colored_prompt.trim_end();
-        //        let mut initial_command = Some(String::new());
-        //        let mut readline = Err(ReadlineError::Eof);
-        //        while let Some(ref cmd) = initial_command {
-        //            readline = rl.readline_with_initial(&prompt, (&cmd, ""));
-        //            initial_command = None;
-        //        }
-        //        rl.helper_mut().expect("No helper").colored_prompt = colored_prompt;
-        let readline = rl.readline(&prompt);
+        if let Ok(line) = &readline {
+            if line.contains(keybindings::COMMAND_START) {
+                // a `command:` binding was used, put its output in the line and edit it again
+                match expand_commands(line, &mut context) {
+                    Ok(line) => initial = Some(line),
+                    Err(err) => report_error(line, &err, &config.theme),
+                }
+                continue;
+            }
+            if !line.trim().is_empty() {
+                rl.add_history_entry(line.trim_end());
+                if let Some(path) = &history_path {
+                    let _ = std::fs::create_dir_all(path.parent().unwrap_or(path));
+                    let _ = rl.save_history(path);
+                }
+            }
+        }
         let line = process_line(readline, &mut context, false);
         match line {
             LineResult::Success(_) => {}
-            LineResult::Error(l, err) => match &err.error {
-                ProximateShellError::ParseError(_, _) => {
-                    println!("\x1b[31m{}\n{}\x1b[0m", l, err);
-                }
-                ProximateShellError::RuntimeError(_) => {
-                    println!("\x1b[31m{}\x1b[0m", err);
-                }
-            },
+            LineResult::Error(l, err) => report_error(&l, &err, &config.theme),
             LineResult::CtrlC => {
                 if ctrlcbreak {
                     std::process::exit(0);
@@ -93,6 +129,82 @@ pub fn cli() -> Result<(), ShellError> {
     Ok(())
 }
 
+/// Shows which shell is active in the prompt, once `enter` started more than one
+fn shell_label(context: &Context) -> String {
+    match context.shell.len() {
+        1 => String::new(),
+        len => format!("[{}/{}] ", context.shell.current_index() + 1, len),
+    }
+}
+
+/// Applies the editor settings of the config, replacing the key bindings applied before.
+/// Invalid settings keep the default, they are reported when the config is loaded or set.
+fn apply_config(rl: &mut Editor<helper::Helper>, config: &Config, bound_keys: &mut Vec<KeyPress>) {
+    rl.set_max_history_size(config.history_size);
+    rl.set_edit_mode(keybindings::edit_mode(&config.edit_mode).unwrap_or(EditMode::Emacs));
+    rl.set_bell_style(keybindings::bell_style(&config.bell_style).unwrap_or_default());
+    rl.set_completion_type(
+        keybindings::completion_type(&config.completion_type).unwrap_or(CompletionType::Circular),
+    );
+    for key in bound_keys.drain(..) {
+        rl.unbind_sequence(key);
+    }
+    for (key, action) in config.keybindings.iter() {
+        if let (Some(key), Some(cmd)) =
+            (keybindings::parse_key(key), keybindings::parse_cmd(action))
+        {
+            rl.bind_sequence(key, cmd);
+            bound_keys.push(key);
+        }
+    }
+}
+
+/// Replaces the pipelines inserted by `command:` bindings by their output, the values are
+/// joined with spaces
+fn expand_commands(line: &str, context: &mut Context) -> Result<String, ShellError> {
+    let mut expanded = String::new();
+    let mut rest = line;
+    while let Some(start) = rest.find(keybindings::COMMAND_START) {
+        expanded.push_str(&rest[..start]);
+        rest = &rest[start + keybindings::COMMAND_START.len_utf8()..];
+        let end = rest.find(keybindings::COMMAND_END).unwrap_or(rest.len());
+        let source = rest[..end].to_string();
+        rest = &rest[(end + keybindings::COMMAND_END.len_utf8()).min(rest.len())..];
+        let block = Block {
+            params: vec![],
+            pipeline: parser::parse(&source)?,
+            source,
+        };
+        let output = output_items(run_block(&block, context, None)?);
+        let output: Vec<_> = output.iter().map(|value| value.to_string()).collect();
+        expanded.push_str(&output.join(" "));
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// Runs the startup script line by line, errors are reported without stopping the script
+fn run_rc_file(context: &mut Context) {
+    let contents = match config::rc_path().map(std::fs::read_to_string) {
+        Some(Ok(contents)) => contents,
+        _ => return,
+    };
+    for (number, line) in contents.lines().enumerate() {
+        if line.trim_start().starts_with('#') {
+            continue;
+        }
+        if let LineResult::Error(l, err) = process_line(Ok(line.to_string()), context, false) {
+            let theme = context.config.lock().theme.clone();
+            let err = err.with_note(format!("in the startup script, line {}", number + 1));
+            report_error(&l, &err, &theme);
+        }
+    }
+}
+
+fn report_error(line: &str, err: &ShellError, theme: &Theme) {
+    print!("{}", diagnostic::render(err, line, theme));
+}
+
 enum LineResult {
     Success(String),
     Error(String, ShellError),
@@ -109,14 +221,23 @@ fn process_line(
         Ok(line) if line.trim().is_empty() => LineResult::Success(line.clone()),
         Ok(line) => {
             let line = chomp_newline(line);
+            // a Ctrl+C pressed during the last line must not stop this one
+            ctx.ctrl_c.store(false, Ordering::SeqCst);
             let result = parser::parse(line)
                 .and_then(|pipeline| classify_pipeline(&pipeline, ctx, line))
-                .and_then(|pipeline| run_pipeline(pipeline, ctx, None, line));
+                .and_then(|pipeline| run_pipeline(pipeline, ctx, None));
             match result {
                 Ok(output) => {
                     if let Some(output) = output {
-                        for val in output {
-                            println!("{}", val.to_string());
+                        let output = output_items(Some(output));
+                        let theme = ctx.config.lock().theme.clone();
+                        match table::format_table(&output, &theme) {
+                            Some(table) => print!("{}", table),
+                            None => {
+                                for val in output {
+                                    println!("{}", val.to_string());
+                                }
+                            }
                         }
                     };
                     LineResult::Success(line.to_string())
@@ -142,6 +263,100 @@ pub fn classify_pipeline(
     pipeline: &SpannedToken,
     context: &Context,
     source: &str,
+) -> Result<ClassifiedPipeline, ShellError> {
+    match expand_aliases(pipeline, context, source, &[])? {
+        Some(expanded) => parser::parse(&expanded)
+            .and_then(|pipeline| classify_commands(&pipeline, context, &expanded))
+            .map_err(|err| err.with_source(expanded.clone())),
+        None => classify_commands(pipeline, context, source),
+    }
+}
+
+/// Replaces the aliases at the head of the pipeline elements by their expansion, keeping the rest
+/// of the element as extra arguments, and adds the default flags of the config to the commands.
+/// Returns `None` if there was nothing to expand.
+///
+/// An alias that expands to a command of the same name, like `alias ls = ls -a`, runs that
+/// command instead of expanding again, any other cycle is an error.
+fn expand_aliases(
+    pipeline: &SpannedToken,
+    context: &Context,
+    source: &str,
+    chain: &[String],
+) -> Result<Option<String>, ShellError> {
+    let pipeline = match &pipeline.item {
+        Token::Pipeline(pipeline) => pipeline,
+        _ => return Ok(None),
+    };
+    let mut expanded = false;
+    let mut parts = vec![];
+    for elem in pipeline.parts.iter() {
+        let text = elem.tokens.span.slice(source);
+        let alias = match elem.tokens.item.first() {
+            Some(head) => match head.item {
+                Token::Bare | Token::GlobPattern | Token::ExternalWord => {
+                    let name = head.span.slice(source);
+                    if chain.last().map(String::as_str) == Some(name) {
+                        None
+                    } else {
+                        context
+                            .aliases
+                            .get(name)
+                            .map(|expansion| (head, name, expansion))
+                    }
+                }
+                _ => None,
+            },
+            None => None,
+        };
+        match alias {
+            Some((head, name, expansion)) => {
+                if chain.iter().any(|n| n == name) {
+                    let mut chain = chain.to_vec();
+                    chain.push(name.to_string());
+                    return Err(ShellError::runtime_error(format!(
+                        "alias loop: {}",
+                        chain.join(" -> ")
+                    )));
+                }
+                let rest = Span::new(head.span.end(), elem.tokens.span.end()).slice(source);
+                let text = format!("{}{}", expansion, rest);
+                let mut chain = chain.to_vec();
+                chain.push(name.to_string());
+                let pipeline = parser::parse(&text)?;
+                parts.push(expand_aliases(&pipeline, context, &text, &chain)?.unwrap_or(text));
+                expanded = true;
+            }
+            None => {
+                let head = elem.tokens.item.first().map(|head| head.span);
+                let flags = head.and_then(|head| {
+                    let name = head.slice(source);
+                    let config = context.config.lock();
+                    config.default_flags.get(name).cloned()
+                });
+                match (head, flags) {
+                    (Some(head), Some(flags)) => {
+                        let rest = Span::new(head.end(), elem.tokens.span.end()).slice(source);
+                        parts.push(format!("{} {}{}", head.slice(source), flags, rest));
+                        expanded = true;
+                    }
+                    _ => parts.push(text.to_string()),
+                }
+            }
+        }
+    }
+    let background = if pipeline.background.is_some() { " &" } else { "" };
+    Ok(if expanded {
+        Some(format!("{}{}", parts.join(" | "), background))
+    } else {
+        None
+    })
+}
+
+fn classify_commands(
+    pipeline: &SpannedToken,
+    context: &Context,
+    source: &str,
 ) -> Result<ClassifiedPipeline, ShellError> {
     let span = pipeline.span;
     match &pipeline.item {
@@ -149,7 +364,16 @@ pub fn classify_pipeline(
             let mut commands = vec![];
             for elem in pipeline.parts.iter() {
                 let mut tokens = elem.tokens.item.iter().cloned();
-                let head: SpannedToken = tokens.next().expect("expected command");
+                let head: SpannedToken = match tokens.next() {
+                    Some(head) => head,
+                    None => {
+                        return Err(ProximateShellError::ParseError(
+                            elem.span(),
+                            Some("expected command".to_string()),
+                        )
+                        .start());
+                    }
+                };
                 let name = match head.item {
                     Token::Bare | Token::GlobPattern | Token::ExternalWord => {
                         head.span.slice(source)
@@ -188,7 +412,7 @@ pub fn classify_pipeline(
                     };
                     commands.push(ClassifiedCommand::Internal(command));
                 } else {
-                    let args = ExternalArgs::from_tokens(&mut tokens, source, elem.span());
+                    let args = ExternalArgs::from_tokens(&mut tokens, source, &context.scope, elem.span());
                     let command = ExternalCommand {
                         name: name.to_string(),
                         name_span: head.span,
@@ -197,18 +421,19 @@ pub fn classify_pipeline(
                     commands.push(ClassifiedCommand::External(command));
                 }
             }
-            Ok(ClassifiedPipeline {
-                commands: Commands {
-                    list: commands,
-                    span,
-                },
-            })
+            let mut classified = ClassifiedPipeline::commands(commands, span, source);
+            classified.background = pipeline.background.is_some();
+            Ok(classified)
         }
-        _ => panic!("expected token"),
+        token => Err(ProximateShellError::ParseError(
+            span,
+            Some(format!("expected pipeline found: {}", token.desc())),
+        )
+        .start()),
     }
 }
 
-fn create_default_context() -> Context {
+pub(crate) fn create_default_context() -> Context {
     let mut context = Context::basic();
     #[inline]
     fn command(c: impl Command + 'static) -> BoxedCommand {
@@ -220,10 +445,60 @@ fn create_default_context() -> Context {
             command(Ls),
             command(Cd),
             command(Mkdir),
+            command(Cp),
             command(Exit),
             command(Help),
             command(Pwd),
             command(Count),
+            command(Open),
+            command(Cat),
+            command(Save),
+            command(FromJson),
+            command(ToJson),
+            command(FromCsv),
+            command(ToCsv),
+            command(FromToml),
+            command(ToToml),
+            command(FromSsv),
+            command(FromLines),
+            command(Where),
+            command(SortBy),
+            command(First),
+            command(Last),
+            command(Skip),
+            command(Select),
+            command(Get),
+            command(Reverse),
+            command(Uniq),
+            command(Each),
+            command(Reduce),
+            command(ParEach),
+            command(If),
+            command(For),
+            command(While),
+            command(Break),
+            command(Continue),
+            command(Def),
+            command(Alias),
+            command(Unalias),
+            command(ConfigCommand),
+            command(History),
+            command(Try),
+            command(Jobs),
+            command(Fg),
+            command(Bg),
+            command(Kill),
+            command(Enter),
+            command(Shells),
+            command(Next),
+            command(Previous),
+            command(Pushd),
+            command(Popd),
+            command(Dirs),
+            command(Z),
+            command(Which),
+            command(Hash),
+            command(Rehash),
         ])
     }
     context
@@ -0,0 +1,95 @@
+use unicode_width::UnicodeWidthStr;
+
+use crate::cli::colors::{ANSI_BOLD, RESET};
+use crate::config::Theme;
+use crate::evaluate::Value;
+
+/// Renders a list of rows as an aligned table, one line per row with an index column. Returns
+/// `None` if any of the values is not a row.
+pub fn format_table(values: &[Value], theme: &Theme) -> Option<String> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut headers: Vec<String> = vec![];
+    for value in values {
+        match value {
+            Value::Row(d) => {
+                for key in d.keys() {
+                    if !headers.contains(key) {
+                        headers.push(key.clone());
+                    }
+                }
+            }
+            _ => return None,
+        }
+    }
+    let rows: Vec<Vec<String>> = values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let mut cells = vec![i.to_string()];
+            if let Value::Row(d) = value {
+                for header in headers.iter() {
+                    let cell = d.get(header).map(Value::to_string).unwrap_or_default();
+                    cells.push(cell.replace('\n', " "));
+                }
+            }
+            cells
+        })
+        .collect();
+    let mut widths: Vec<usize> = std::iter::once("#")
+        .chain(headers.iter().map(String::as_str))
+        .map(UnicodeWidthStr::width)
+        .collect();
+    for row in rows.iter() {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.width());
+        }
+    }
+
+    let mut out = String::new();
+    let header_cells = std::iter::once("#".to_string())
+        .chain(headers.iter().cloned())
+        .collect::<Vec<_>>();
+    let header_style = format!("{}{}", ANSI_BOLD, theme.header());
+    out.push_str(&format_line(
+        &header_cells,
+        &widths,
+        &header_style,
+        theme.index(),
+    ));
+    out.push_str(
+        &widths
+            .iter()
+            .map(|w| "─".repeat(w + 2))
+            .collect::<Vec<_>>()
+            .join("┼"),
+    );
+    out.push('\n');
+    for row in rows.iter() {
+        out.push_str(&format_line(row, &widths, "", theme.index()));
+    }
+    Some(out)
+}
+
+/// Formats one line of the table, `style` applies to every cell and the index column is colored
+/// unless a style is given
+fn format_line(cells: &[String], widths: &[usize], style: &str, index: &str) -> String {
+    let line = cells
+        .iter()
+        .zip(widths.iter())
+        .enumerate()
+        .map(|(i, (cell, width))| {
+            let padding = " ".repeat(width - cell.width());
+            if !style.is_empty() {
+                format!(" {}{}{}{} ", style, cell, RESET, padding)
+            } else if i == 0 {
+                format!(" {}{}{}{} ", index, cell, RESET, padding)
+            } else {
+                format!(" {}{} ", cell, padding)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("│");
+    format!("{}\n", line.trim_end())
+}
@@ -0,0 +1,87 @@
+use crate::commands::Command;
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::dict::Dictionary;
+use crate::evaluate::{CallInfo, Value};
+use crate::parser::syntax_shape::SyntaxShape;
+use crate::signature::Signature;
+
+pub struct Alias;
+
+impl Command for Alias {
+    fn name(&self) -> &str {
+        "alias"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("alias")
+            .rest(
+                SyntaxShape::Any,
+                "the name, = and the text it expands to, taken verbatim",
+            )
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Define an alias, eg `alias ll = ls --long`, or list the aliases without arguments. Extra arguments are appended to the expansion."
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        _input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        let raw = call_info.raw_args();
+        if raw.is_empty() {
+            return Ok(Some(
+                context
+                    .aliases
+                    .list()
+                    .into_iter()
+                    .map(|(name, expansion)| alias_row(name, expansion))
+                    .collect(),
+            ));
+        }
+        let (name, expansion) = match raw.find('=') {
+            Some(eq) => (raw[..eq].trim(), raw[eq + 1..].trim()),
+            None => {
+                let expansion = context
+                    .aliases
+                    .get(raw)
+                    .ok_or_else(|| ShellError::runtime_error(format!("Unknown alias: {}", raw)))?;
+                return Ok(Some(vec![alias_row(raw.to_string(), expansion)]));
+            }
+        };
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err(ShellError::runtime_error(format!(
+                "Invalid alias name: {}",
+                name
+            )));
+        }
+        // quotes let the expansion contain a pipeline, eg `alias big = "ls | where size > 1mb"`
+        let expansion = if expansion.len() >= 2
+            && (expansion.starts_with('"') && expansion.ends_with('"')
+                || expansion.starts_with('\'') && expansion.ends_with('\''))
+        {
+            &expansion[1..expansion.len() - 1]
+        } else {
+            expansion
+        };
+        if expansion.is_empty() {
+            return Err(ShellError::runtime_error(format!(
+                "expected the text {} expands to after =",
+                name
+            )));
+        }
+        context.aliases.insert(name, expansion);
+        Ok(None)
+    }
+}
+
+fn alias_row(name: String, expansion: String) -> Value {
+    let mut row = Dictionary::default();
+    row.insert("name", Value::String(name));
+    row.insert("expansion", Value::String(expansion));
+    Value::Row(row)
+}
@@ -0,0 +1,44 @@
+use crate::commands::fg::job_arg;
+use crate::commands::Command;
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::{CallInfo, Value};
+use crate::parser::syntax_shape::SyntaxShape;
+use crate::signature::Signature;
+
+pub struct Bg;
+
+impl Command for Bg {
+    fn name(&self) -> &str {
+        "bg"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("bg")
+            .optional(
+                "job",
+                SyntaxShape::Any,
+                "the job as %N or N, defaults to the last stopped one",
+            )
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Continue a stopped job in the background."
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        _input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        let id = job_arg(&call_info)?;
+        let (id, command) = context
+            .jobs
+            .resume(id)
+            .map_err(|err| err.at(call_info.nth_span(0)))?;
+        println!("[{}] {} &", id, command);
+        Ok(None)
+    }
+}
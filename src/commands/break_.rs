@@ -0,0 +1,25 @@
+use crate::commands::Command;
+use crate::context::Context;
+use crate::error::{ProximateShellError, ShellError};
+use crate::evaluate::{CallInfo, Value};
+
+pub struct Break;
+
+impl Command for Break {
+    fn name(&self) -> &str {
+        "break"
+    }
+
+    fn usage(&self) -> &str {
+        "Leave the innermost for or while loop."
+    }
+
+    fn run(
+        &self,
+        _call_info: CallInfo,
+        _input: Option<Vec<Value>>,
+        _context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        Err(ProximateShellError::Break.start())
+    }
+}
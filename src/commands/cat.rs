@@ -0,0 +1,59 @@
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::commands::open::{lines, read_file};
+use crate::commands::{Command, RunnableContext};
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::{CallInfo, Value};
+use crate::parser::syntax_shape::SyntaxShape;
+use crate::signature::Signature;
+
+#[derive(Deserialize)]
+pub struct CatArgs {
+    pub rest: Vec<PathBuf>,
+}
+
+pub struct Cat;
+
+impl Command for Cat {
+    fn name(&self) -> &str {
+        "cat"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("cat")
+            .rest(SyntaxShape::Path, "the files to display")
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Display the contents of text files, line by line."
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        call_info.process(context, cat, input)?.run()
+    }
+}
+
+fn cat(
+    CatArgs { rest: files }: CatArgs,
+    ctx: &RunnableContext,
+) -> Result<Option<Vec<Value>>, ShellError> {
+    let cwd = ctx.shell.cwd();
+    let mut results = vec![];
+    for file in files {
+        results.extend(lines(&read_file(&ctx.shell, &cwd.join(file))?));
+    }
+    if results.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(results))
+    }
+}
@@ -1,168 +1,317 @@
-use std::io::{BufRead, BufReader, Write};
-use std::process::{Command, Stdio};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::Ordering;
 
+use crate::commands::cd::CdArgs;
+use crate::commands::output_items;
 use crate::context::Context;
-use crate::error::ShellError;
+use crate::error::{ErrorKind, ShellError};
 use crate::evaluate::Value;
+use crate::expand;
+use crate::jobs::{self, Job};
 use crate::parser::command::classified::external::ExternalCommand;
+use crate::parser::command::classified::{ClassifiedCommand, ClassifiedPipeline};
+use crate::parser::span::Span;
+use crate::suggestions;
 
 pub(crate) fn run_external_command(
     command: ExternalCommand,
-    _context: &mut Context,
+    context: &mut Context,
     input: Option<Vec<Value>>,
     is_last: bool,
 ) -> Result<Option<Vec<Value>>, ShellError> {
-    if !did_find_command(&command.name) {
-        return Err(ShellError::runtime_error("Command not found"));
-    }
-    let args = command
-        .args
-        .iter()
-        .map(|arg| {
-            let arg = expand_tilde(arg.as_str(), dirs::home_dir);
-
-            #[cfg(not(windows))]
-            {
-                if argument_contains_whitespace(&arg) && argument_is_quoted(&arg) {
-                    if let Some(unquoted) = remove_quotes(&arg) {
-                        format!(r#""{}""#, unquoted)
-                    } else {
-                        arg.as_ref().to_string()
-                    }
-                } else {
-                    arg.as_ref().to_string()
-                }
-            }
-            #[cfg(windows)]
-            {
-                if let Some(unquoted) = remove_quotes(&arg) {
-                    unquoted.to_string()
-                } else {
-                    arg.as_ref().to_string()
-                }
-            }
-        })
-        .collect::<Vec<String>>();
-    let mut process = {
-        #[cfg(windows)]
-        {
-            let mut process = Command::new("cmd");
-            process.arg("/c");
-            process.arg(&command.name);
-            for arg in args {
-                process.arg(&arg);
-            }
-            process
+    let name_span = command.name_span;
+    let cwd = current_dir(context)?;
+    if !did_find_command(&command.name, &cwd, context) {
+        let name = expand::expand_word(&command.name, expand::env_var)
+            .unwrap_or_else(|_| command.name.clone());
+        let dir = cwd.join(name);
+        let is_dir = dir.is_dir();
+        if is_dir && command.args.iter().next().is_none() && context.config.lock().auto_cd {
+            return context.shell.cd(CdArgs { dst: Some(dir) });
         }
-
-        #[cfg(not(windows))]
-        {
-            let cmd_with_args = vec![command.name.clone(), args.join(" ")].join(" ");
-            let mut process = Command::new("sh");
-            process.arg("-c").arg(cmd_with_args);
-            process
-        }
-    };
+        return Err(command_not_found(&command, is_dir, context));
+    }
+    let mut process = external_process(&command, &cwd, context)?;
+    context.path_cache.hit(&command.name);
+    // every command gets the terminal while it runs so that Ctrl+C goes to it and not the shell,
+    // only the last one can be stopped with Ctrl+Z as the shell reads the output of the others
+    jobs::set_process_group(&mut process, 0, is_last, true);
     if !is_last {
         process.stdout(Stdio::piped());
     }
     if input.is_some() {
         process.stdin(Stdio::piped());
     }
-    if let Ok(mut child) = process.spawn() {
-        if let Some(input) = input {
-            let mut stdin_write = child
-                .stdin
-                .take()
-                .expect("Internal error: could not get stdin pipe for external command");
-            for val in input {
-                if let Err(e) = stdin_write.write(val.to_string().as_bytes()) {
+    let mut child = process.spawn().map_err(|e| spawn_error(&command, e))?;
+    jobs::join_process_group(child.id(), 0);
+    if is_last {
+        write_input(&mut child, input, name_span)?;
+        context
+            .jobs
+            .wait_foreground(Job::new(command_text(&command), vec![child]), None)?;
+        return Ok(None);
+    }
+    let output = context.jobs.foreground(child.id(), || {
+        write_input(&mut child, input, name_span)?;
+        read_output(&mut child, name_span)
+    });
+    context
+        .jobs
+        .wait_foreground(Job::new(command_text(&command), vec![child]), None)?;
+    if context.ctrl_c.load(Ordering::SeqCst) {
+        // the output is cut short, don't pass it on
+        return Ok(None);
+    }
+    Ok(Some(output?))
+}
+
+/// Writes the input values to the stdin of a command, one per line
+fn write_input(
+    child: &mut Child,
+    input: Option<Vec<Value>>,
+    name_span: Span,
+) -> Result<(), ShellError> {
+    if let Some(input) = input {
+        let mut stdin_write = child
+            .stdin
+            .take()
+            .expect("Internal error: could not get stdin pipe for external command");
+        for val in output_items(Some(input)) {
+            let mut line = val.to_string();
+            if !line.ends_with('\n') {
+                line.push('\n');
+            }
+            match stdin_write.write(line.as_bytes()) {
+                Ok(_) => {}
+                // the command doesn't read its input, eg `echo` inside of `each`
+                Err(ref e) if e.kind() == io::ErrorKind::BrokenPipe => break,
+                Err(e) => {
                     let message = format!("Unable to write to stdin (error = {})", e);
-                    return Err(ShellError::runtime_error(message));
+                    return Err(ShellError::new(ErrorKind::ExternalFailed, message).at(name_span));
                 }
             }
         }
-        return if !is_last {
-            let stdout = if let Some(stdout) = child.stdout.take() {
-                stdout
-            } else {
-                return Err(ShellError::runtime_error("can't redirect stdout"));
-            };
-            let mut buf_reader = BufReader::new(stdout);
-            let mut results = vec![];
-            let mut buf = String::new();
-            while let Ok(n) = buf_reader.read_line(&mut buf) {
-                if n == 0 {
-                    break;
-                }
-                results.push(Value::String(buf.as_str().to_string()));
-                buf.clear();
-            }
-            Ok(Some(results))
-        } else {
-            child
-                .wait()
-                .map_err(|_| ShellError::runtime_error("command's not running"))
-                .map(|_| None)
-        };
     }
-    Ok(None)
+    Ok(())
 }
 
-fn did_find_command(name: &str) -> bool {
-    #[cfg(not(windows))]
-    {
-        which::which(name).is_ok()
+/// Reads the stdout of a command until it is closed, one value per line
+fn read_output(child: &mut Child, name_span: Span) -> Result<Vec<Value>, ShellError> {
+    let stdout = if let Some(stdout) = child.stdout.take() {
+        stdout
+    } else {
+        return Err(
+            ShellError::new(ErrorKind::ExternalFailed, "can't redirect stdout").at(name_span),
+        );
+    };
+    let mut buf_reader = BufReader::new(stdout);
+    let mut results = vec![];
+    let mut buf = String::new();
+    while let Ok(n) = buf_reader.read_line(&mut buf) {
+        if n == 0 {
+            break;
+        }
+        let line = buf.trim_end_matches(&['\n', '\r'][..]);
+        results.push(Value::String(line.to_string()));
+        buf.clear();
     }
+    Ok(results)
+}
 
-    #[cfg(windows)]
-    {
-        if which::which(name).is_ok() {
-            true
-        } else {
-            let cmd_builtins = [
-                "call", "cls", "color", "date", "dir", "echo", "find", "hostname", "pause",
-                "start", "time", "title", "ver", "copy", "mkdir", "rename", "rd", "rmdir", "type",
-            ];
-
-            cmd_builtins.contains(&name)
+/// Starts a pipeline of external commands as a background job, the commands are connected with
+/// pipes and share a process group. The job doesn't read the terminal.
+pub(crate) fn run_background_job(
+    pipeline: ClassifiedPipeline,
+    context: &mut Context,
+) -> Result<Option<Vec<Value>>, ShellError> {
+    let cwd = current_dir(context)?;
+    let mut commands = vec![];
+    for command in pipeline.commands.list.into_iter() {
+        match command {
+            ClassifiedCommand::External(command)
+                if did_find_command(&command.name, &cwd, context) =>
+            {
+                commands.push(command)
+            }
+            ClassifiedCommand::External(command) => {
+                return Err(command_not_found(&command, false, context))
+            }
+            ClassifiedCommand::Internal(command) => {
+                return Err(ShellError::new(
+                    ErrorKind::InvalidInput,
+                    format!("{} is an internal command, it can't run in the background", command.name),
+                )
+                .at(command.name_span)
+                .with_help("only pipelines of external commands can be jobs"));
+            }
         }
     }
+    let processes = commands
+        .iter()
+        .map(|command| external_process(command, &cwd, context))
+        .collect::<Result<Vec<_>, _>>()?;
+    for command in commands.iter() {
+        context.path_cache.hit(&command.name);
+    }
+    let mut children: Vec<Child> = vec![];
+    let count = commands.len();
+    for (i, (command, mut process)) in commands.iter().zip(processes).enumerate() {
+        let pgid = children.first().map_or(0, Child::id);
+        jobs::set_process_group(&mut process, pgid, true, false);
+        match children.last_mut().and_then(|previous| previous.stdout.take()) {
+            Some(stdout) => process.stdin(stdout),
+            None => process.stdin(Stdio::null()),
+        };
+        if i + 1 < count {
+            process.stdout(Stdio::piped());
+        }
+        match process.spawn() {
+            Ok(child) => {
+                jobs::join_process_group(child.id(), pgid);
+                children.push(child);
+            }
+            Err(e) => {
+                for child in children.iter_mut() {
+                    let _ = child.kill();
+                }
+                return Err(spawn_error(command, e));
+            }
+        }
+    }
+    let pgid = children.first().map_or(0, Child::id);
+    let text = pipeline.source.trim_end().trim_end_matches('&').trim_end();
+    let id = context.jobs.add(Job::new(text, children));
+    println!("[{}] {}", id, pgid);
+    Ok(None)
 }
 
-fn expand_tilde<SI: ?Sized, P, HD>(input: &SI, home_dir: HD) -> std::borrow::Cow<str>
-where
-    SI: AsRef<str>,
-    P: AsRef<std::path::Path>,
-    HD: FnOnce() -> Option<P>,
-{
-    shellexpand::tilde_with_context(input, home_dir)
+fn command_text(command: &ExternalCommand) -> String {
+    std::iter::once(&command.name)
+        .map(String::as_str)
+        .chain(command.args.iter().map(|arg| arg.arg.as_str()))
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
-pub fn argument_contains_whitespace(argument: &str) -> bool {
-    argument.chars().any(|c| c.is_whitespace())
+fn spawn_error(command: &ExternalCommand, e: io::Error) -> ShellError {
+    ShellError::new(
+        ErrorKind::ExternalFailed,
+        format!("Could not run {}: {}", command.name, e),
+    )
+    .at(command.name_span)
 }
 
-fn argument_is_quoted(argument: &str) -> bool {
-    if argument.len() < 2 {
-        return false;
+/// The directory of the current shell, which external commands run in
+fn current_dir(context: &Context) -> Result<PathBuf, ShellError> {
+    let cwd = context.shell.cwd();
+    if cwd.is_dir() {
+        Ok(cwd)
+    } else {
+        Err(ShellError::new(
+            ErrorKind::NotFound,
+            format!("The current directory {} doesn't exist anymore", cwd.display()),
+        )
+        .with_help("use `cd` to change to another directory"))
     }
+}
 
-    (argument.starts_with('"') && argument.ends_with('"')
-        || (argument.starts_with('\'') && argument.ends_with('\'')))
+/// The process running an external command in `cwd`, with its arguments expanded by the shell
+fn external_process(
+    command: &ExternalCommand,
+    cwd: &Path,
+    context: &Context,
+) -> Result<Command, ShellError> {
+    let literal = context.config.lock().glob_no_match == "literal";
+    let mut args = vec![];
+    for arg in command.args.iter() {
+        if !arg.expand {
+            args.push(arg.arg.clone());
+            continue;
+        }
+        let word = expand::expand_word(&arg.arg, expand::env_var).map_err(|err| err.at(arg.span))?;
+        for word in expand::expand_braces(&word) {
+            let paths = expand::expand_glob(&word, cwd, literal).map_err(|err| err.at(arg.span))?;
+            args.extend(paths);
+        }
+    }
+    #[cfg(windows)]
+    {
+        // the builtins of cmd like `dir` aren't programs
+        let mut process = Command::new("cmd");
+        process.current_dir(cwd);
+        process.arg("/c");
+        process.arg(&command.name);
+        process.args(args);
+        Ok(process)
+    }
+
+    #[cfg(not(windows))]
+    {
+        use std::os::unix::process::CommandExt;
+
+        // a relative path like `./build.sh` is found in the directory of the shell, which isn't
+        // the one of the process
+        let program = context
+            .path_cache
+            .resolve(&command.name, cwd)
+            .unwrap_or_else(|| PathBuf::from(&command.name));
+        let mut process = Command::new(program);
+        process.arg0(&command.name);
+        process.current_dir(cwd);
+        process.args(args);
+        Ok(process)
+    }
 }
 
-#[allow(unused)]
-fn add_quotes(argument: &str) -> String {
-    format!("'{}'", argument)
+/// The error for an unknown command, suggesting the internal commands, aliases and executables
+/// with a similar name
+fn command_not_found(command: &ExternalCommand, is_dir: bool, context: &Context) -> ShellError {
+    let err = ShellError::new(
+        ErrorKind::NotFound,
+        format!("Command not found: {}", command.name),
+    )
+    .at(command.name_span);
+    if is_dir {
+        return err.with_help(format!(
+            "{} is a directory, use `cd {}` or `config set auto_cd true`",
+            command.name, command.name
+        ));
+    }
+    let candidates = context
+        .registry
+        .names()
+        .into_iter()
+        .chain(context.aliases.list().into_iter().map(|(name, _)| name))
+        .chain(context.path_cache.names());
+    let similar = suggestions::suggest(&command.name, candidates);
+    if similar.is_empty() {
+        err
+    } else {
+        let similar: Vec<_> = similar.iter().map(|name| format!("`{}`", name)).collect();
+        err.with_help(format!("did you mean {}?", similar.join(", ")))
+    }
 }
 
-fn remove_quotes(argument: &str) -> Option<&str> {
-    if !argument_is_quoted(argument) {
-        return None;
+fn did_find_command(name: &str, cwd: &Path, context: &Context) -> bool {
+    let found = context.path_cache.resolve(name, cwd).is_some();
+    #[cfg(not(windows))]
+    {
+        found
     }
 
-    let size = argument.len();
+    #[cfg(windows)]
+    {
+        if found {
+            true
+        } else {
+            let cmd_builtins = [
+                "call", "cls", "color", "date", "dir", "echo", "find", "hostname", "pause",
+                "start", "time", "title", "ver", "copy", "mkdir", "rename", "rd", "rmdir", "type",
+            ];
 
-    Some(&argument[1..size - 1])
+            cmd_builtins.contains(&name)
+        }
+    }
 }
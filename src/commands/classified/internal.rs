@@ -1,3 +1,4 @@
+use crate::commands::list_items;
 use crate::context::Context;
 use crate::error::ShellError;
 use crate::evaluate::Value;
@@ -10,5 +11,13 @@ pub(crate) fn run_internal_command(
     source: &str,
 ) -> Result<Option<Vec<Value>>, ShellError> {
     let internal_command = context.expect_command(command.name.as_str())?;
-    context.run_command(internal_command, command.args, source, input)
+    if internal_command.takes_lists() {
+        return context.run_command(internal_command, command.args, source, input);
+    }
+    let is_filter = internal_command.is_filter();
+    let (input, is_list) = list_items(input);
+    match context.run_command(internal_command, command.args, source, input)? {
+        Some(output) if is_list && is_filter => Ok(Some(vec![Value::List(output)])),
+        output => Ok(output),
+    }
 }
@@ -1,33 +1,69 @@
-use crate::commands::classified::external::run_external_command;
+use std::sync::atomic::Ordering;
+
+use crate::cli::classify_pipeline;
+use crate::commands::classified::external::{run_background_job, run_external_command};
 use crate::commands::classified::internal::run_internal_command;
 use crate::context::Context;
 use crate::error::ShellError;
+use crate::evaluate::block::Block;
 use crate::evaluate::Value;
 use crate::parser::command::classified::{ClassifiedCommand, ClassifiedPipeline};
 
 pub(crate) fn run_pipeline(
+    pipeline: ClassifiedPipeline,
+    ctx: &mut Context,
+    input: Option<Vec<Value>>,
+) -> Result<Option<Vec<Value>>, ShellError> {
+    if pipeline.background {
+        let line = pipeline.source.clone();
+        return run_background_job(pipeline, ctx).map_err(|err| err.with_source(line));
+    }
+    run_commands(pipeline, ctx, input, false)
+}
+
+/// Runs the commands of a pipeline. Unless `capture` is set, an external command at the end of the
+/// pipeline writes straight to the terminal.
+fn run_commands(
     pipeline: ClassifiedPipeline,
     ctx: &mut Context,
     mut input: Option<Vec<Value>>,
-    line: &str,
+    capture: bool,
 ) -> Result<Option<Vec<Value>>, ShellError> {
+    let line = pipeline.source;
     let mut iter = pipeline.commands.list.into_iter().peekable();
     loop {
+        // Ctrl+C stops the pipeline, the commands after the interrupted one aren't run
+        if ctx.ctrl_c.load(Ordering::SeqCst) {
+            return Ok(None);
+        }
         let item = iter.next();
         let next = iter.peek();
 
         input = match (item, next) {
             (Some(ClassifiedCommand::Internal(command)), _) => {
-                run_internal_command(command, ctx, input, line)?
+                run_internal_command(command, ctx, input, &line)?
             }
             (Some(ClassifiedCommand::External(command)), None) => {
-                run_external_command(command, ctx, input, true)?
+                run_external_command(command, ctx, input, !capture)
+                    .map_err(|err| err.with_source(line.as_str()))?
             }
             (Some(ClassifiedCommand::External(command)), _) => {
-                run_external_command(command, ctx, input, false)?
+                run_external_command(command, ctx, input, false)
+                    .map_err(|err| err.with_source(line.as_str()))?
             }
             _ => break,
         }
     }
     Ok(input)
 }
+
+/// Classifies and runs the pipeline of a block with the given input, the output of a trailing
+/// external command is captured as well
+pub(crate) fn run_block(
+    block: &Block,
+    ctx: &mut Context,
+    input: Option<Vec<Value>>,
+) -> Result<Option<Vec<Value>>, ShellError> {
+    let pipeline = classify_pipeline(&block.pipeline, ctx, &block.source)?;
+    run_commands(pipeline, ctx, input, true)
+}
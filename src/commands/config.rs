@@ -0,0 +1,90 @@
+use crate::commands::Command;
+use crate::config::config_path;
+use crate::context::Context;
+use crate::error::{ErrorKind, ShellError};
+use crate::evaluate::operator::parse_literal;
+use crate::evaluate::{CallInfo, Value};
+use crate::parser::syntax_shape::SyntaxShape;
+use crate::signature::Signature;
+
+pub struct ConfigCommand;
+
+impl Command for ConfigCommand {
+    fn name(&self) -> &str {
+        "config"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("config")
+            .optional(
+                "action",
+                SyntaxShape::String,
+                "get, set or path, shows all the settings if omitted",
+            )
+            .optional(
+                "key",
+                SyntaxShape::String,
+                "the setting, eg history_size or theme.prompt",
+            )
+            .optional("value", SyntaxShape::Any, "the new value for set")
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Inspect and change the settings, eg `config set edit_mode vi`. Changes are saved to the config file."
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        _input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        let args = &call_info.args;
+        let action = args.nth(0).map(Value::to_string);
+        let key = args.nth(1).map(Value::to_string);
+        match (action.as_deref(), key) {
+            (None, _) => Ok(Some(vec![context.config.lock().to_value()?])),
+            (Some("get"), Some(key)) => Ok(Some(vec![context
+                .config
+                .lock()
+                .get(&key)
+                .map_err(|err| err.at(call_info.nth_span(1)))?])),
+            (Some("set"), Some(key)) => {
+                let value = match args.nth(2) {
+                    Some(Value::String(s)) => parse_literal(s),
+                    Some(value) => value.clone(),
+                    None => {
+                        return Err(ShellError::runtime_error(format!(
+                            "expected a value for {}",
+                            key
+                        ))
+                        .at(call_info.nth_span(1)))
+                    }
+                };
+                let mut config = context.config.lock().clone();
+                // an unknown setting is the key's fault, a value that doesn't fit the value's
+                config.set(&key, &value).map_err(|err| {
+                    let at = match err.kind() {
+                        Some(ErrorKind::NotFound) => 1,
+                        _ => 2,
+                    };
+                    err.at(call_info.nth_span(at))
+                })?;
+                config.save()?;
+                *context.config.lock() = config;
+                Ok(None)
+            }
+            (Some("path"), _) => Ok(Some(config_path().map(Value::Path).into_iter().collect())),
+            (Some("get"), None) | (Some("set"), None) => {
+                Err(ShellError::runtime_error("expected the name of a setting")
+                    .at(call_info.nth_span(0)))
+            }
+            (Some(action), _) => Err(ShellError::runtime_error(format!(
+                "Unknown action: {}, expected get, set or path",
+                action
+            ))
+            .at(call_info.nth_span(0))),
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use crate::commands::Command;
+use crate::context::Context;
+use crate::error::{ProximateShellError, ShellError};
+use crate::evaluate::{CallInfo, Value};
+
+pub struct Continue;
+
+impl Command for Continue {
+    fn name(&self) -> &str {
+        "continue"
+    }
+
+    fn usage(&self) -> &str {
+        "Skip to the next iteration of the innermost for or while loop."
+    }
+
+    fn run(
+        &self,
+        _call_info: CallInfo,
+        _input: Option<Vec<Value>>,
+        _context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        Err(ProximateShellError::Continue.start())
+    }
+}
@@ -1,13 +1,9 @@
-use std::sync::atomic::AtomicBool;
-use std::sync::Arc;
-
 use num_bigint::BigInt;
 
 use crate::commands::Command;
-use crate::context::CommandRegistry;
+use crate::context::Context;
 use crate::error::ShellError;
 use crate::evaluate::{CallInfo, Value};
-use crate::shell::Shell;
 
 pub struct Count;
 
@@ -24,9 +20,7 @@ impl Command for Count {
         &self,
         _call_info: CallInfo,
         input: Option<Vec<Value>>,
-        _ctrl_c: Arc<AtomicBool>,
-        _shell: Arc<dyn Shell>,
-        _registry: &CommandRegistry,
+        _context: &mut Context,
     ) -> Result<Option<Vec<Value>>, ShellError> {
         Ok(Some(vec![Value::Int(BigInt::from(
             input.map(|v| v.len()).unwrap_or(0),
@@ -1,15 +1,12 @@
 use std::path::PathBuf;
-use std::sync::atomic::AtomicBool;
-use std::sync::Arc;
 
 use serde::Deserialize;
 
 use crate::commands::{Command, RunnableContext};
-use crate::context::CommandRegistry;
-use crate::error::ShellError;
+use crate::context::Context;
+use crate::error::{ErrorKind, ShellError};
 use crate::evaluate::{CallInfo, Value};
 use crate::parser::syntax_shape::SyntaxShape;
-use crate::shell::Shell;
 use crate::signature::Signature;
 
 #[derive(Deserialize)]
@@ -26,7 +23,7 @@ impl Command for Cp {
     }
 
     fn usage(&self) -> &str {
-        "Copy files."
+        "Copy files and directories, or extract them from an archive shell."
     }
     fn signature(&self) -> Signature {
         Signature::build("cp")
@@ -38,11 +35,16 @@ impl Command for Cp {
         &self,
         call_info: CallInfo,
         input: Option<Vec<Value>>,
-        ctrl_c: Arc<AtomicBool>,
-        shell: Arc<dyn Shell>,
-        _registry: &CommandRegistry,
+        context: &mut Context,
     ) -> Result<Option<Vec<Value>>, ShellError> {
-        call_info.process(&shell, ctrl_c, cp, input)?.run()
+        call_info
+            .process(context, cp, input)?
+            .run()
+            .map_err(|err| match err.kind() {
+                // the source or the files matching it are missing
+                Some(ErrorKind::NotFound) => err.at(call_info.nth_span(0)),
+                _ => err.at(call_info.span_from(0)),
+            })
     }
 }
 
@@ -0,0 +1,299 @@
+use std::sync::Arc;
+
+use crate::commands::classified::pipeline::run_block;
+use crate::commands::{expect_block, Command};
+use crate::context::Context;
+use crate::error::{ProximateShellError, ShellError};
+use crate::evaluate::block::Block;
+use crate::evaluate::operator::parse_literal;
+use crate::evaluate::{CallInfo, Value};
+use crate::parser::syntax_shape::SyntaxShape;
+use crate::signature::Signature;
+
+pub struct Def;
+
+impl Command for Def {
+    fn name(&self) -> &str {
+        "def"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("def")
+            .required("name", SyntaxShape::String, "the name of the new command")
+            .required(
+                "params",
+                SyntaxShape::Any,
+                "the parameters, eg [name, count?: int, --verbose, --sep: string, ...rest]",
+            )
+            .required("block", SyntaxShape::Block, "the body of the command")
+            .named(
+                "usage",
+                SyntaxShape::String,
+                "the description shown by help",
+            )
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Define a command, eg `def greet [name] { echo hello $name }`. The parameters are bound as variables while the body runs."
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        _input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        let args = &call_info.args;
+        let name = match args.nth(0) {
+            Some(Value::String(name)) => name.clone(),
+            _ => {
+                return Err(ShellError::runtime_error(
+                    "expected the name of the command",
+                ))
+            }
+        };
+        let params = match args.nth(1) {
+            Some(Value::List(params)) => parse_params(params)?,
+            _ => {
+                return Err(ShellError::runtime_error(
+                    "expected the parameters in brackets, eg [name]",
+                ))
+            }
+        };
+        let block = expect_block(args.nth(2))?;
+        let usage = match args.get("usage") {
+            Some(usage) => usage.to_string(),
+            None => default_usage(&params),
+        };
+
+        let mut signature = Signature::build(name.as_str()).desc(usage);
+        for param in params.iter() {
+            signature = match param.kind {
+                ParamKind::Required => signature.required(param.name.as_str(), param.shape, ""),
+                ParamKind::Optional => signature.optional(param.name.as_str(), param.shape, ""),
+                ParamKind::Rest => signature.rest(param.shape, ""),
+                ParamKind::Switch => signature.switch(param.name.as_str(), ""),
+                ParamKind::Named => signature.named(param.name.as_str(), param.shape, ""),
+            };
+        }
+        let command = UserCommand {
+            name: name.clone(),
+            signature,
+            params,
+            block,
+        };
+        context.registry.insert(name, Arc::new(command));
+        Ok(None)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum ParamKind {
+    Required,
+    Optional,
+    Rest,
+    Switch,
+    Named,
+}
+
+#[derive(Debug, Clone)]
+struct Param {
+    name: String,
+    kind: ParamKind,
+    shape: SyntaxShape,
+}
+
+/// The usage of a command defined without `--usage`, listing its parameters as they were declared
+fn default_usage(params: &[Param]) -> String {
+    if params.is_empty() {
+        return "User-defined command.".to_string();
+    }
+    let params = params
+        .iter()
+        .map(|param| match param.kind {
+            ParamKind::Required => param.name.clone(),
+            ParamKind::Optional => format!("{}?", param.name),
+            ParamKind::Rest => format!("...{}", param.name),
+            ParamKind::Switch | ParamKind::Named => format!("--{}", param.name),
+        })
+        .collect::<Vec<_>>();
+    format!("User-defined command taking {}.", params.join(", "))
+}
+
+/// Parses a parameter list like `[name, count?: int, --verbose, --sep: string, ...rest]`.
+/// A type may be written right after the colon or as the next item.
+fn parse_params(items: &[Value]) -> Result<Vec<Param>, ShellError> {
+    let mut params: Vec<Param> = vec![];
+    let mut items = items.iter().map(Value::to_string).peekable();
+    while let Some(item) = items.next() {
+        let (item, shape) = match item.find(':') {
+            Some(colon) if colon + 1 == item.len() => {
+                let shape = items.next().ok_or_else(|| {
+                    ShellError::runtime_error(format!("expected a type after {}", item))
+                })?;
+                (item[..colon].to_string(), Some(shape))
+            }
+            Some(colon) => (
+                item[..colon].to_string(),
+                Some(item[colon + 1..].to_string()),
+            ),
+            None => (item, None),
+        };
+        let typed = shape.is_some();
+        let shape = match shape {
+            Some(shape) => SyntaxShape::from_name(shape.trim())
+                .ok_or_else(|| ShellError::runtime_error(format!("Unknown type: {}", shape)))?,
+            None => SyntaxShape::Any,
+        };
+        let (name, kind) = if let Some(flag) = item.strip_prefix("--") {
+            let kind = if typed {
+                ParamKind::Named
+            } else {
+                ParamKind::Switch
+            };
+            (flag, kind)
+        } else if let Some(rest) = item.strip_prefix("...") {
+            (rest, ParamKind::Rest)
+        } else if let Some(optional) = item.strip_suffix('?') {
+            (optional, ParamKind::Optional)
+        } else {
+            (item.as_str(), ParamKind::Required)
+        };
+        if name.is_empty() {
+            return Err(ShellError::runtime_error(format!(
+                "Invalid parameter: {}",
+                item
+            )));
+        }
+        let positional = |p: &Param| p.kind != ParamKind::Switch && p.kind != ParamKind::Named;
+        if let Some(last) = params.iter().rev().find(|p| positional(p)) {
+            if last.kind == ParamKind::Rest && kind != ParamKind::Switch && kind != ParamKind::Named
+            {
+                return Err(ShellError::runtime_error(
+                    "the rest parameter must be the last positional parameter",
+                ));
+            }
+            if last.kind == ParamKind::Optional && kind == ParamKind::Required {
+                return Err(ShellError::runtime_error(format!(
+                    "the required parameter {} can't follow an optional one",
+                    name
+                )));
+            }
+        }
+        params.push(Param {
+            name: name.to_string(),
+            kind,
+            shape,
+        });
+    }
+    Ok(params)
+}
+
+/// A command declared with `def`
+pub struct UserCommand {
+    name: String,
+    signature: Signature,
+    params: Vec<Param>,
+    block: Block,
+}
+
+impl Command for UserCommand {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn signature(&self) -> Signature {
+        self.signature.clone()
+    }
+
+    fn usage(&self) -> &str {
+        &self.signature.usage
+    }
+
+    /// The input goes to the body as it is
+    fn takes_lists(&self) -> bool {
+        true
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        let args = &call_info.args;
+        let saved_scope = context.scope.clone();
+        let mut position = 0;
+        for param in self.params.iter() {
+            let (value, span) = match param.kind {
+                ParamKind::Required | ParamKind::Optional => {
+                    position += 1;
+                    (
+                        args.nth(position - 1).cloned(),
+                        call_info.nth_span(position - 1),
+                    )
+                }
+                ParamKind::Rest => (
+                    Some(Value::List(args.slice_from(position))),
+                    call_info.span_from(position),
+                ),
+                ParamKind::Switch => (
+                    Some(Value::Boolean(args.has(&param.name))),
+                    call_info.named_span(&param.name),
+                ),
+                ParamKind::Named => (
+                    args.get(&param.name).cloned(),
+                    call_info.named_span(&param.name),
+                ),
+            };
+            let value = match value {
+                Some(Value::List(list)) if param.kind == ParamKind::Rest => Value::List(
+                    list.into_iter()
+                        .map(|value| coerce(value, param))
+                        .collect::<Result<_, _>>()
+                        .map_err(|err| err.at(span))?,
+                ),
+                Some(value) => coerce(value, param).map_err(|err| err.at(span))?,
+                None => Value::Nothing,
+            };
+            context.scope.insert(param.name.as_str(), value);
+        }
+        let output = run_block(&self.block, context, input).map_err(|err| match err.error {
+            ProximateShellError::Break | ProximateShellError::Continue => err,
+            _ => ShellError::runtime_error(format!("`{}` failed", self.name))
+                .with_label(call_info.name_span, "in this call")
+                .with_source(call_info.source.clone())
+                .with_cause(err.with_source(self.block.source.clone())),
+        });
+        context.scope = saved_scope;
+        output
+    }
+}
+
+/// Converts an argument to the type declared for its parameter
+fn coerce(value: Value, param: &Param) -> Result<Value, ShellError> {
+    let value = match (param.shape, value) {
+        (SyntaxShape::Int, Value::String(s)) | (SyntaxShape::Number, Value::String(s)) => {
+            parse_literal(&s)
+        }
+        (SyntaxShape::Path, Value::String(s)) => Value::Path(s.into()),
+        (SyntaxShape::Pattern, Value::String(s)) => Value::Pattern(s),
+        (_, value) => value,
+    };
+    match (param.shape, &value) {
+        (SyntaxShape::Int, Value::Int(_))
+        | (SyntaxShape::Number, Value::Int(_))
+        | (SyntaxShape::Number, Value::Number(_))
+        | (SyntaxShape::Block, Value::Block(_)) => Ok(value),
+        (SyntaxShape::Int, _) | (SyntaxShape::Number, _) | (SyntaxShape::Block, _) => {
+            Err(ShellError::type_mismatch(format!(
+                "{} for {}, found {}",
+                param.shape,
+                param.name,
+                value.type_name()
+            )))
+        }
+        _ => Ok(value),
+    }
+}
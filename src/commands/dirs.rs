@@ -0,0 +1,70 @@
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::commands::{Command, RunnableContext};
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::{CallInfo, Value};
+use crate::signature::Signature;
+
+#[derive(Deserialize)]
+pub struct DirsArgs {
+    pub history: bool,
+}
+
+pub struct Dirs;
+
+impl Command for Dirs {
+    fn name(&self) -> &str {
+        "dirs"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("dirs")
+            .switch(
+                "history",
+                "list the directories changed to instead, the oldest first",
+            )
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Display the current directory followed by the directory stack of pushd."
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        call_info.process(context, dirs, input)?.run()
+    }
+}
+
+fn dirs(
+    DirsArgs { history }: DirsArgs,
+    ctx: &RunnableContext,
+) -> Result<Option<Vec<Value>>, ShellError> {
+    let dirs = if history {
+        ctx.shell.dir_history()
+    } else {
+        ctx.shell.dirs()
+    };
+    let home = ctx.shell.homedir();
+    Ok(Some(
+        dirs.into_iter()
+            .map(|dir| Value::String(abbreviate_home(&dir, home.as_deref())))
+            .collect(),
+    ))
+}
+
+/// Writes the home directory at the start of a path as `~`
+fn abbreviate_home(path: &str, home: Option<&Path>) -> String {
+    match home.and_then(|home| Path::new(path).strip_prefix(home).ok()) {
+        Some(rest) if rest.as_os_str().is_empty() => "~".to_string(),
+        Some(rest) => Path::new("~").join(rest).to_string_lossy().to_string(),
+        None => path.to_string(),
+    }
+}
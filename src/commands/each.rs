@@ -0,0 +1,61 @@
+use std::sync::atomic::Ordering;
+
+use crate::commands::classified::pipeline::run_block;
+use crate::commands::{expect_block, output_items, Command};
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::{CallInfo, Value};
+use crate::parser::syntax_shape::SyntaxShape;
+use crate::signature::Signature;
+
+pub struct Each;
+
+impl Command for Each {
+    fn name(&self) -> &str {
+        "each"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("each")
+            .required(
+                "block",
+                SyntaxShape::Block,
+                "the block to run for each value",
+            )
+            .switch("ignore-errors", "skip the values the block fails on")
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Run a block once per input value, eg `ls | each { get name }`. The value is bound to $it, or to the parameter of the block like `{ |file| ... }`, and is also the input of the block."
+    }
+
+    fn is_filter(&self) -> bool {
+        true
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        let block = expect_block(call_info.args.nth(0))?;
+        let ignore_errors = call_info.args.has("ignore-errors");
+        let saved_scope = context.scope.clone();
+        let mut results = vec![];
+        for value in input.unwrap_or_default() {
+            if context.ctrl_c.load(Ordering::SeqCst) {
+                break;
+            }
+            context.scope.insert(block.param(0, "it"), value.clone());
+            let output = run_block(&block, context, Some(vec![value]));
+            context.scope = saved_scope.clone();
+            match output {
+                Err(err) if ignore_errors && !err.is_loop_control() => {}
+                output => results.extend(output_items(output?)),
+            }
+        }
+        Ok(Some(results))
+    }
+}
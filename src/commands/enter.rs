@@ -0,0 +1,112 @@
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::commands::open::read_file;
+use crate::commands::{Command, RunnableContext};
+use crate::context::Context;
+use crate::error::{ErrorKind, ShellError};
+use crate::evaluate::{CallInfo, Value};
+use crate::format::Format;
+use crate::parser::syntax_shape::SyntaxShape;
+use crate::shell::{ArchiveKind, ArchiveShell, ValueShell};
+use crate::signature::Signature;
+
+#[derive(Deserialize)]
+pub struct EnterArgs {
+    pub location: Option<PathBuf>,
+}
+
+pub struct Enter;
+
+impl Command for Enter {
+    fn name(&self) -> &str {
+        "enter"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("enter")
+            .optional(
+                "location",
+                SyntaxShape::Path,
+                "the directory, data file or archive to start the new shell in, defaults to the input",
+            )
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Create a new shell and begin at this path, or in the data of a file or of the input."
+    }
+
+    fn takes_lists(&self) -> bool {
+        true
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        call_info
+            .process(context, enter, input)?
+            .run()
+            .map_err(|err| err.at(call_info.nth_span(0)))
+    }
+}
+
+fn enter(
+    EnterArgs { location }: EnterArgs,
+    ctx: &RunnableContext,
+) -> Result<Option<Vec<Value>>, ShellError> {
+    let location = match location {
+        Some(location) => location,
+        None => {
+            let value = match ctx.input.clone() {
+                Some(mut values) if values.len() == 1 => values.remove(0),
+                Some(values) if !values.is_empty() => Value::List(values),
+                _ => {
+                    return Err(ShellError::new(
+                        ErrorKind::InvalidInput,
+                        "Expected a location or some input to enter",
+                    ))
+                }
+            };
+            ctx.shell
+                .insert_at_current(Box::new(ValueShell::new(value, ctx.shell.cwd(), None)));
+            return Ok(None);
+        }
+    };
+    let path = ctx.shell.cwd().join(&location);
+    if let Some(shell) = ctx.shell.enter_dir(&path) {
+        ctx.shell.insert_at_current(shell);
+        return Ok(None);
+    }
+    let path = dunce::canonicalize(&path).map_err(|e| ShellError::io(e, &location))?;
+    if let Some(kind) = ArchiveKind::from_path(&path) {
+        let shell = ArchiveShell::open(path, kind, ctx.shell.cwd())?;
+        ctx.shell.insert_at_current(Box::new(shell));
+        return Ok(None);
+    }
+    match Format::from_path(&path) {
+        Some(format) => {
+            let value = format.parse(&read_file(&ctx.shell, &path)?)?;
+            ctx.shell.insert_at_current(Box::new(ValueShell::new(
+                value,
+                ctx.shell.cwd(),
+                Some(path),
+            )));
+            Ok(None)
+        }
+        None => Err(ShellError::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "{} is not a directory, a data file or an archive",
+                location.to_string_lossy()
+            ),
+        )
+        .with_help(
+            "files are read according to their extension, eg .json, .toml, .tar.gz or .zip",
+        )),
+    }
+}
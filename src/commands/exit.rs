@@ -1,11 +1,8 @@
-use std::sync::atomic::AtomicBool;
-use std::sync::Arc;
-
 use crate::commands::Command;
-use crate::context::CommandRegistry;
+use crate::context::Context;
 use crate::error::ShellError;
 use crate::evaluate::{CallInfo, Value};
-use crate::shell::Shell;
+use crate::signature::Signature;
 
 pub struct Exit;
 
@@ -14,18 +11,25 @@ impl Command for Exit {
         "exit"
     }
 
+    fn signature(&self) -> Signature {
+        Signature::build("exit")
+            .switch("now", "exit out of all shells immediately")
+            .desc(self.usage())
+    }
+
     fn usage(&self) -> &str {
         "Exit the current shell (or all shells)"
     }
     fn run(
         &self,
-        _call_info: CallInfo,
+        call_info: CallInfo,
         _input: Option<Vec<Value>>,
-        _ctrl_c: Arc<AtomicBool>,
-        _shell: Arc<dyn Shell>,
-        _registry: &CommandRegistry,
+        context: &mut Context,
     ) -> Result<Option<Vec<Value>>, ShellError> {
-        // TODO: save history
-        std::process::exit(0);
+        if call_info.args.has("now") || !context.shell.remove_at_current() {
+            // TODO: save history
+            std::process::exit(0);
+        }
+        Ok(None)
     }
 }
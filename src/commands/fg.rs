@@ -0,0 +1,60 @@
+use crate::commands::Command;
+use crate::context::Context;
+use crate::error::{ErrorKind, ShellError};
+use crate::evaluate::{CallInfo, Value};
+use crate::jobs::parse_job;
+use crate::parser::syntax_shape::SyntaxShape;
+use crate::signature::Signature;
+
+pub struct Fg;
+
+impl Command for Fg {
+    fn name(&self) -> &str {
+        "fg"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("fg")
+            .optional(
+                "job",
+                SyntaxShape::Any,
+                "the job as %N or N, defaults to the last one",
+            )
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Bring a job to the foreground and wait for it."
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        _input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        let id = job_arg(&call_info)?;
+        let (id, job) = context
+            .jobs
+            .take(id)
+            .map_err(|err| err.at(call_info.nth_span(0)))?;
+        println!("{}", job.command);
+        context.jobs.wait_foreground(job, Some(id))?;
+        Ok(None)
+    }
+}
+
+/// The job given as the first argument, if any
+pub(crate) fn job_arg(call_info: &CallInfo) -> Result<Option<usize>, ShellError> {
+    match call_info.args.nth(0) {
+        None => Ok(None),
+        Some(value) => match parse_job(&value.to_string()) {
+            Some(id) => Ok(Some(id)),
+            None => Err(ShellError::new(
+                ErrorKind::InvalidInput,
+                format!("{} is not a job, expected %N or N", value.to_string()),
+            )
+            .at(call_info.nth_span(0))),
+        },
+    }
+}
@@ -0,0 +1,56 @@
+use serde::Deserialize;
+
+use crate::commands::{Command, RunnableContext};
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::{CallInfo, Value};
+use crate::parser::syntax_shape::SyntaxShape;
+use crate::signature::Signature;
+
+#[derive(Deserialize)]
+pub struct FirstArgs {
+    pub rows: Option<usize>,
+}
+
+pub struct First;
+
+impl Command for First {
+    fn name(&self) -> &str {
+        "first"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("first")
+            .optional(
+                "rows",
+                SyntaxShape::Int,
+                "the number of rows to keep, defaults to 1",
+            )
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Keep only the first rows of the input."
+    }
+
+    fn is_filter(&self) -> bool {
+        true
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        call_info.process(context, first, input)?.run()
+    }
+}
+
+fn first(
+    FirstArgs { rows }: FirstArgs,
+    ctx: &RunnableContext,
+) -> Result<Option<Vec<Value>>, ShellError> {
+    let values = ctx.input.clone().unwrap_or_default();
+    Ok(Some(values.into_iter().take(rows.unwrap_or(1)).collect()))
+}
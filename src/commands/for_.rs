@@ -0,0 +1,88 @@
+use std::sync::atomic::Ordering;
+
+use crate::commands::classified::pipeline::run_block;
+use crate::commands::{expect_block, output_items, Command};
+use crate::context::Context;
+use crate::error::{ProximateShellError, ShellError};
+use crate::evaluate::{CallInfo, Value};
+use crate::parser::syntax_shape::SyntaxShape;
+use crate::signature::Signature;
+
+pub struct For;
+
+impl Command for For {
+    fn name(&self) -> &str {
+        "for"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("for")
+            .required("variable", SyntaxShape::String, "the name of the loop variable")
+            .required("in", SyntaxShape::String, "the keyword in")
+            .rest(
+                SyntaxShape::Any,
+                "the values to loop over followed by the block, the input is used if no value is given",
+            )
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Run a block for each value, eg `for f in a.txt b.txt { open $f }`."
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        let variable = call_info
+            .args
+            .nth(0)
+            .map(Value::to_string)
+            .unwrap_or_default();
+        if call_info.args.nth(1).map(Value::to_string).as_deref() != Some("in") {
+            return Err(ShellError::runtime_error(
+                "expected in after the loop variable",
+            ));
+        }
+        let mut rest = call_info.args.slice_from(2);
+        let block = expect_block(rest.pop().as_ref())?;
+        let values = if rest.is_empty() {
+            input.unwrap_or_default()
+        } else {
+            rest.into_iter()
+                .flat_map(|value| match value {
+                    Value::List(list) => list,
+                    value => vec![value],
+                })
+                .collect()
+        };
+        // the loop variable only exists in the loop, an outer one of the same name is restored
+        let saved_scope = context.scope.clone();
+        let mut results = vec![];
+        let mut error = None;
+        for value in values {
+            if context.ctrl_c.load(Ordering::SeqCst) {
+                break;
+            }
+            context.scope.insert(variable.as_str(), value);
+            match run_block(&block, context, None) {
+                Ok(output) => results.extend(output_items(output)),
+                Err(err) => match err.error {
+                    ProximateShellError::Break => break,
+                    ProximateShellError::Continue => continue,
+                    _ => {
+                        error = Some(err);
+                        break;
+                    }
+                },
+            }
+        }
+        context.scope = saved_scope;
+        match error {
+            Some(err) => Err(err),
+            None => Ok(Some(results)),
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use serde::Deserialize;
+
+use crate::commands::{input_as_string, Command, RunnableContext};
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::{CallInfo, Value};
+use crate::format::csv::from_csv_string_to_value;
+use crate::parser::syntax_shape::SyntaxShape;
+use crate::signature::Signature;
+
+#[derive(Deserialize)]
+pub struct FromCsvArgs {
+    pub headerless: bool,
+    pub separator: Option<String>,
+}
+
+pub struct FromCsv;
+
+impl Command for FromCsv {
+    fn name(&self) -> &str {
+        "from-csv"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("from-csv")
+            .switch(
+                "headerless",
+                "don't treat the first row as column names, name them Column1, Column2...",
+            )
+            .named(
+                "separator",
+                SyntaxShape::String,
+                "a character to separate columns, defaults to ','",
+            )
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Parse text as CSV and create rows."
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        call_info.process(context, from_csv, input)?.run()
+    }
+}
+
+fn from_csv(
+    FromCsvArgs {
+        headerless,
+        separator,
+    }: FromCsvArgs,
+    ctx: &RunnableContext,
+) -> Result<Option<Vec<Value>>, ShellError> {
+    let separator = separator_byte(separator, b',')?;
+    let text = input_as_string(ctx.input.clone())?;
+    match from_csv_string_to_value(&text, separator, headerless)? {
+        Value::List(rows) => Ok(Some(vec![Value::List(rows)])),
+        value => Ok(Some(vec![value])),
+    }
+}
+
+/// Checks that a user supplied separator is a single ASCII character
+pub(crate) fn separator_byte(separator: Option<String>, default: u8) -> Result<u8, ShellError> {
+    match separator {
+        None => Ok(default),
+        Some(s) => {
+            let s = if s == "\\t" { "\t".to_string() } else { s };
+            match s.as_bytes() {
+                [b] if b.is_ascii() => Ok(*b),
+                _ => Err(ShellError::runtime_error(format!(
+                    "separator must be a single character, found '{}'",
+                    s
+                ))),
+            }
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use serde::Deserialize;
+
+use crate::commands::{input_as_string, Command, RunnableContext};
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::{CallInfo, Value};
+use crate::format::json::from_json_string_to_value;
+use crate::signature::Signature;
+
+#[derive(Deserialize)]
+pub struct FromJsonArgs {
+    pub objects: bool,
+}
+
+pub struct FromJson;
+
+impl Command for FromJson {
+    fn name(&self) -> &str {
+        "from-json"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("from-json")
+            .switch("objects", "treat each line as a separate JSON value")
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Parse text as JSON and create rows."
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        call_info.process(context, from_json, input)?.run()
+    }
+}
+
+fn from_json(
+    FromJsonArgs { objects }: FromJsonArgs,
+    ctx: &RunnableContext,
+) -> Result<Option<Vec<Value>>, ShellError> {
+    let text = input_as_string(ctx.input.clone())?;
+    if objects {
+        let values = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(from_json_string_to_value)
+            .collect::<Result<Vec<_>, _>>()?;
+        return Ok(Some(vec![Value::List(values)]));
+    }
+    match from_json_string_to_value(&text)? {
+        Value::List(items) => Ok(Some(vec![Value::List(items)])),
+        value => Ok(Some(vec![value])),
+    }
+}
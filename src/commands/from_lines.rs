@@ -0,0 +1,34 @@
+use crate::commands::{input_as_string, Command};
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::{CallInfo, Value};
+
+pub struct FromLines;
+
+impl Command for FromLines {
+    fn name(&self) -> &str {
+        "from-lines"
+    }
+
+    fn usage(&self) -> &str {
+        "Split text into lines, dropping the line endings."
+    }
+
+    fn run(
+        &self,
+        _call_info: CallInfo,
+        input: Option<Vec<Value>>,
+        _context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        let text = input_as_string(input)?;
+        let lines = text
+            .lines()
+            .map(|line| Value::String(line.to_string()))
+            .collect::<Vec<_>>();
+        if lines.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(lines))
+        }
+    }
+}
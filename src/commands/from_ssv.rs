@@ -0,0 +1,87 @@
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::commands::{input_as_string, Command, RunnableContext};
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::dict::Dictionary;
+use crate::evaluate::{CallInfo, Value};
+use crate::signature::Signature;
+
+#[derive(Deserialize)]
+pub struct FromSsvArgs {
+    pub headerless: bool,
+}
+
+pub struct FromSsv;
+
+impl Command for FromSsv {
+    fn name(&self) -> &str {
+        "from-ssv"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("from-ssv")
+            .switch(
+                "headerless",
+                "don't treat the first line as column names, name them Column1, Column2...",
+            )
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Parse space-separated columns, like the output of `ps` or `df`, and create rows."
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        call_info.process(context, from_ssv, input)?.run()
+    }
+}
+
+fn from_ssv(
+    FromSsvArgs { headerless }: FromSsvArgs,
+    ctx: &RunnableContext,
+) -> Result<Option<Vec<Value>>, ShellError> {
+    let text = input_as_string(ctx.input.clone())?;
+    let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+    let headers = if headerless {
+        vec![]
+    } else {
+        match lines.next() {
+            Some(header) => header.split_whitespace().map(String::from).collect(),
+            None => return Ok(None),
+        }
+    };
+    let rows = lines
+        .map(|line| Value::Row(split_row(line, &headers)))
+        .collect::<Vec<_>>();
+    Ok(Some(rows))
+}
+
+/// Splits a line into as many columns as there are headers, the last column takes the rest of the
+/// line so that values containing spaces (eg a command line) stay whole.
+fn split_row(line: &str, headers: &[String]) -> Dictionary {
+    let mut entries = IndexMap::new();
+    let mut rest = line.trim();
+    let mut i = 0;
+    while !rest.is_empty() {
+        let is_last = !headers.is_empty() && i == headers.len() - 1;
+        let (field, next) = match rest.find(char::is_whitespace) {
+            Some(end) if !is_last => (&rest[..end], rest[end..].trim_start()),
+            _ => (rest, ""),
+        };
+        let name = match headers.get(i) {
+            Some(name) => name.clone(),
+            None => format!("Column{}", i + 1),
+        };
+        entries.insert(name, Value::String(field.to_string()));
+        rest = next;
+        i += 1;
+    }
+    Dictionary::new(entries)
+}
@@ -0,0 +1,27 @@
+use crate::commands::{input_as_string, Command};
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::{CallInfo, Value};
+use crate::format::toml::from_toml_string_to_value;
+
+pub struct FromToml;
+
+impl Command for FromToml {
+    fn name(&self) -> &str {
+        "from-toml"
+    }
+
+    fn usage(&self) -> &str {
+        "Parse text as TOML and create a row."
+    }
+
+    fn run(
+        &self,
+        _call_info: CallInfo,
+        input: Option<Vec<Value>>,
+        _context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        let text = input_as_string(input)?;
+        Ok(Some(vec![from_toml_string_to_value(&text)?]))
+    }
+}
@@ -0,0 +1,76 @@
+use serde::Deserialize;
+
+use crate::commands::{Command, RunnableContext};
+use crate::context::Context;
+use crate::error::{ErrorKind, ShellError};
+use crate::evaluate::{CallInfo, Value};
+use crate::parser::syntax_shape::SyntaxShape;
+use crate::signature::Signature;
+
+#[derive(Deserialize)]
+pub struct GetArgs {
+    pub rest: Vec<String>,
+}
+
+pub struct Get;
+
+impl Command for Get {
+    fn name(&self) -> &str {
+        "get"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("get")
+            .rest(
+                SyntaxShape::String,
+                "the column(s) to extract, nested columns are written as a.b",
+            )
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Extract the values of columns from each row."
+    }
+
+    fn is_filter(&self) -> bool {
+        true
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        call_info
+            .process(context, get, input)?
+            .run()
+            .map_err(|err| err.at(call_info.span_from(0)))
+    }
+}
+
+fn get(
+    GetArgs { rest: columns }: GetArgs,
+    ctx: &RunnableContext,
+) -> Result<Option<Vec<Value>>, ShellError> {
+    if columns.is_empty() {
+        return Err(ShellError::runtime_error("get needs at least one column"));
+    }
+    let mut results = vec![];
+    for value in ctx.input.clone().unwrap_or_default() {
+        for column in columns.iter() {
+            match value.get_data_by_column_path(column) {
+                // lists are flattened so that `get` on a list column yields its items
+                Some(Value::List(items)) => results.extend(items.iter().cloned()),
+                Some(cell) => results.push(cell.clone()),
+                None => {
+                    return Err(ShellError::new(
+                        ErrorKind::NotFound,
+                        format!("Unknown column: {}", column),
+                    ));
+                }
+            }
+        }
+    }
+    Ok(Some(results))
+}
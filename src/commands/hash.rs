@@ -0,0 +1,63 @@
+use crate::commands::Command;
+use crate::context::Context;
+use crate::error::{ErrorKind, ShellError};
+use crate::evaluate::dict::Dictionary;
+use crate::evaluate::{CallInfo, Value};
+use crate::parser::syntax_shape::SyntaxShape;
+use crate::signature::Signature;
+
+pub struct Hash;
+
+impl Command for Hash {
+    fn name(&self) -> &str {
+        "hash"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("hash")
+            .rest(
+                SyntaxShape::String,
+                "commands to look up in PATH and remember without running them",
+            )
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "List the external commands run with how many times and where they were found in PATH."
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        _input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        if !call_info.args.is_empty() {
+            for (pos, name) in call_info.args.slice_from(0).iter().enumerate() {
+                let name = name.to_string();
+                if !context.path_cache.remember(&name) {
+                    return Err(ShellError::new(
+                        ErrorKind::NotFound,
+                        format!("{} is not an executable on PATH", name),
+                    )
+                    .at(call_info.nth_span(pos)));
+                }
+            }
+            return Ok(None);
+        }
+        Ok(Some(
+            context
+                .path_cache
+                .hits()
+                .into_iter()
+                .map(|(command, hits, path)| {
+                    let mut row = Dictionary::default();
+                    row.insert("command", Value::String(command));
+                    row.insert("hits", Value::Int(hits.into()));
+                    row.insert("path", Value::String(path.to_string_lossy().to_string()));
+                    Value::Row(row)
+                })
+                .collect(),
+        ))
+    }
+}
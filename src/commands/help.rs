@@ -1,15 +1,12 @@
 use std::collections::VecDeque;
-use std::sync::atomic::AtomicBool;
-use std::sync::Arc;
 
 use serde::Deserialize;
 
 use crate::commands::Command;
-use crate::context::CommandRegistry;
+use crate::context::Context;
 use crate::error::ShellError;
 use crate::evaluate::{CallInfo, Value};
 use crate::parser::syntax_shape::SyntaxShape;
-use crate::shell::Shell;
 use crate::signature::{NamedType, PositionalType, Signature};
 
 #[derive(Deserialize)]
@@ -38,24 +35,22 @@ impl Command for Help {
         &self,
         call_info: CallInfo,
         _input: Option<Vec<Value>>,
-        _ctrl_c: Arc<AtomicBool>,
-        _shell: Arc<dyn Shell>,
-        registry: &CommandRegistry,
+        context: &mut Context,
     ) -> Result<Option<Vec<Value>>, ShellError> {
         match call_info.args.nth(0) {
             Some(Value::String(s)) => {
                 if s == "commands" {
                     let mut help = VecDeque::new();
-                    let mut sorted_names = registry.names();
+                    let mut sorted_names = context.registry.names();
                     sorted_names.sort();
                     for name in sorted_names.iter() {
-                        if let Some(command) = registry.get_command(name) {
+                        if let Some(command) = context.registry.get_command(name) {
                             help.push_back(format!("{}: {}", name, command.signature().usage))
                         }
                     }
                     Ok(Some(help.into_iter().map(Value::String).collect()))
                 } else {
-                    let command = registry.expect_command(s)?;
+                    let command = context.registry.expect_command(s)?;
                     let signature = command.signature();
                     Ok(Some(get_help(s, signature).into()))
                 }
@@ -0,0 +1,35 @@
+use crate::commands::Command;
+use crate::config::history_path;
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::{CallInfo, Value};
+
+pub struct History;
+
+impl Command for History {
+    fn name(&self) -> &str {
+        "history"
+    }
+
+    fn usage(&self) -> &str {
+        "Output the lines entered before, oldest first."
+    }
+
+    fn run(
+        &self,
+        _call_info: CallInfo,
+        _input: Option<Vec<Value>>,
+        _context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        let contents = match history_path() {
+            Some(path) if path.exists() => std::fs::read_to_string(path)?,
+            _ => return Ok(None),
+        };
+        Ok(Some(
+            contents
+                .lines()
+                .map(|line| Value::String(line.to_string()))
+                .collect(),
+        ))
+    }
+}
@@ -0,0 +1,152 @@
+use crate::commands::classified::pipeline::run_block;
+use crate::commands::Command;
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::block::Block;
+use crate::evaluate::evaluate_expr;
+use crate::evaluate::operator::{parse_literal, Operator};
+use crate::evaluate::{CallInfo, Value};
+use crate::parser::syntax_shape::SyntaxShape;
+use crate::parser::token::Token;
+use crate::signature::Signature;
+
+pub struct If;
+
+impl Command for If {
+    fn name(&self) -> &str {
+        "if"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("if")
+            .rest(
+                SyntaxShape::Any,
+                "the condition, the block to run, then optionally else and another block or if",
+            )
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Run a block if a condition holds, eg `if $it.size > 1kb { echo big } else { echo small }`."
+    }
+
+    fn takes_lists(&self) -> bool {
+        true
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        let mut args = call_info.args.slice_from(0);
+        loop {
+            // the condition takes at least one value, which may be a block itself
+            let then_position = args
+                .iter()
+                .skip(1)
+                .position(|arg| matches!(arg, Value::Block(_)))
+                .map(|position| position + 1)
+                .ok_or_else(|| ShellError::runtime_error("if needs a condition and a block"))?;
+            let rest = args.split_off(then_position + 1);
+            let then_block = match args.pop() {
+                Some(Value::Block(block)) => block,
+                _ => unreachable!("the position of a block was found above"),
+            };
+            if condition(&args, context)? {
+                return run_block(&then_block, context, input);
+            }
+            let mut rest = rest.into_iter();
+            match rest.next() {
+                None => return Ok(None),
+                Some(Value::String(ref s)) if s == "else" => {}
+                Some(value) => {
+                    return Err(ShellError::runtime_error(format!(
+                        "expected else after the block of if, found {}",
+                        value.to_string()
+                    )));
+                }
+            }
+            args = rest.collect();
+            match args.first() {
+                Some(Value::String(s)) if s == "if" => {
+                    args.remove(0);
+                }
+                Some(Value::Block(block)) if args.len() == 1 => {
+                    return run_block(block, context, input);
+                }
+                _ => {
+                    return Err(ShellError::runtime_error(
+                        "expected a block or if after else",
+                    ))
+                }
+            }
+        }
+    }
+}
+
+/// Evaluates the condition of `if` or `while`, which is either
+/// * a single value, tested for truthiness. A word must be a boolean or a number.
+/// * a comparison like `$it.size > 1kb`
+/// * a block, which is evaluated again every time the condition is checked
+pub(crate) fn condition(args: &[Value], context: &mut Context) -> Result<bool, ShellError> {
+    match args {
+        [Value::Block(block)] => block_condition(block, context),
+        [Value::String(s)] => match parse_literal(s) {
+            Value::String(_) => Err(ShellError::type_mismatch(format!(
+                "a boolean or a number as condition, found {}",
+                s
+            ))),
+            literal => Ok(literal.is_true()),
+        },
+        [value] => Ok(value.is_true()),
+        [left, Value::String(operator), right] => compare(left, operator, right),
+        [] => Err(ShellError::runtime_error("expected a condition")),
+        _ => Err(ShellError::runtime_error(
+            "expected a condition like `$it.size > 1kb`",
+        )),
+    }
+}
+
+fn compare(left: &Value, operator: &str, right: &Value) -> Result<bool, ShellError> {
+    let operator = Operator::parse(operator)
+        .ok_or_else(|| ShellError::runtime_error(format!("Unknown operator: {}", operator)))?;
+    let literal = |value: &Value| match value {
+        Value::String(s) => parse_literal(s),
+        value => value.clone(),
+    };
+    operator.apply(&literal(left), &literal(right))
+}
+
+/// A block whose first word is a variable or a literal is evaluated as a condition, eg
+/// `{ $it > 3 }`. Any other block is run as a pipeline and its output is tested for truthiness.
+fn block_condition(block: &Block, context: &mut Context) -> Result<bool, ShellError> {
+    if let Token::Pipeline(pipeline) = &block.pipeline.item {
+        if let [element] = &pipeline.parts[..] {
+            let tokens = element
+                .tokens
+                .item
+                .iter()
+                .filter(|token| !matches!(token.item, Token::Whitespace | Token::Separator))
+                .collect::<Vec<_>>();
+            let is_expression = match tokens.first().map(|token| &token.item) {
+                Some(Token::Variable(_)) | Some(Token::String(_)) => true,
+                Some(Token::Bare) => !matches!(
+                    parse_literal(tokens[0].span.slice(&block.source)),
+                    Value::String(_)
+                ),
+                _ => false,
+            };
+            if is_expression {
+                let values = tokens
+                    .into_iter()
+                    .map(|token| evaluate_expr(token, &block.source, &context.scope))
+                    .collect::<Result<Vec<_>, _>>()?;
+                return condition(&values, context);
+            }
+        }
+    }
+    let output = run_block(block, context, None)?.unwrap_or_default();
+    Ok(output.iter().any(Value::is_true))
+}
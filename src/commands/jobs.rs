@@ -0,0 +1,43 @@
+use crate::commands::Command;
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::dict::Dictionary;
+use crate::evaluate::{CallInfo, Value};
+
+pub struct Jobs;
+
+impl Command for Jobs {
+    fn name(&self) -> &str {
+        "jobs"
+    }
+
+    fn usage(&self) -> &str {
+        "List the jobs running in the background or stopped with Ctrl+Z."
+    }
+
+    fn run(
+        &self,
+        _call_info: CallInfo,
+        _input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        for message in context.jobs.poll() {
+            println!("{}", message);
+        }
+        Ok(Some(
+            context
+                .jobs
+                .list()
+                .into_iter()
+                .map(|(id, pid, status, command)| {
+                    let mut row = Dictionary::default();
+                    row.insert("id", Value::Int(id.into()));
+                    row.insert("pid", Value::Int(pid.into()));
+                    row.insert("status", Value::String(status.name().to_string()));
+                    row.insert("command", Value::String(command));
+                    Value::Row(row)
+                })
+                .collect(),
+        ))
+    }
+}
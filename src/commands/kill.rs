@@ -0,0 +1,76 @@
+use crate::commands::Command;
+use crate::context::Context;
+use crate::error::{ErrorKind, ShellError};
+use crate::evaluate::{CallInfo, Value};
+use crate::jobs::{kill_process, parse_job, parse_signal};
+use crate::parser::syntax_shape::SyntaxShape;
+use crate::signature::Signature;
+
+pub struct Kill;
+
+impl Command for Kill {
+    fn name(&self) -> &str {
+        "kill"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("kill")
+            .named(
+                "signal",
+                SyntaxShape::Any,
+                "the signal to send by name or number, defaults to TERM",
+            )
+            .rest(SyntaxShape::Any, "the jobs as %N, or process ids")
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Send a signal to jobs or processes."
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        _input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        let signal = match call_info.args.get("signal") {
+            None => parse_signal("TERM").unwrap_or(15),
+            Some(value) => {
+                let name = value.to_string();
+                parse_signal(&name).ok_or_else(|| {
+                    ShellError::new(ErrorKind::InvalidInput, format!("Unknown signal: {}", name))
+                        .at(call_info.named_span("signal"))
+                })?
+            }
+        };
+        if call_info.args.is_empty() {
+            return Err(
+                ShellError::new(ErrorKind::InvalidInput, "Expected a job or a process id")
+                    .at(call_info.name_span),
+            );
+        }
+        for (pos, target) in call_info.args.slice_from(0).iter().enumerate() {
+            let target = target.to_string();
+            let result = if target.starts_with('%') {
+                match parse_job(&target) {
+                    Some(id) => context.jobs.signal(id, signal),
+                    None => Err(ShellError::new(
+                        ErrorKind::InvalidInput,
+                        format!("{} is not a job", target),
+                    )),
+                }
+            } else {
+                match target.parse() {
+                    Ok(pid) => kill_process(pid, signal),
+                    Err(_) => Err(ShellError::new(
+                        ErrorKind::InvalidInput,
+                        format!("{} is not a job or a process id", target),
+                    )),
+                }
+            };
+            result.map_err(|err| err.at(call_info.nth_span(pos)))?;
+        }
+        Ok(None)
+    }
+}
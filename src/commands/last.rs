@@ -0,0 +1,57 @@
+use serde::Deserialize;
+
+use crate::commands::{Command, RunnableContext};
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::{CallInfo, Value};
+use crate::parser::syntax_shape::SyntaxShape;
+use crate::signature::Signature;
+
+#[derive(Deserialize)]
+pub struct LastArgs {
+    pub rows: Option<usize>,
+}
+
+pub struct Last;
+
+impl Command for Last {
+    fn name(&self) -> &str {
+        "last"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("last")
+            .optional(
+                "rows",
+                SyntaxShape::Int,
+                "the number of rows to keep, defaults to 1",
+            )
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Keep only the last rows of the input."
+    }
+
+    fn is_filter(&self) -> bool {
+        true
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        call_info.process(context, last, input)?.run()
+    }
+}
+
+fn last(
+    LastArgs { rows }: LastArgs,
+    ctx: &RunnableContext,
+) -> Result<Option<Vec<Value>>, ShellError> {
+    let mut values = ctx.input.clone().unwrap_or_default();
+    let start = values.len().saturating_sub(rows.unwrap_or(1));
+    Ok(Some(values.split_off(start)))
+}
@@ -1,15 +1,12 @@
 use std::path::PathBuf;
-use std::sync::atomic::AtomicBool;
-use std::sync::Arc;
 
 use serde::Deserialize;
 
 use crate::commands::{Command, RunnableContext};
-use crate::context::CommandRegistry;
+use crate::context::Context;
 use crate::error::ShellError;
 use crate::evaluate::{CallInfo, Value};
 use crate::parser::syntax_shape::SyntaxShape;
-use crate::shell::Shell;
 use crate::signature::Signature;
 
 #[derive(Deserialize, Debug)]
@@ -42,11 +39,12 @@ impl Command for Ls {
         &self,
         call_info: CallInfo,
         input: Option<Vec<Value>>,
-        ctrl_c: Arc<AtomicBool>,
-        shell: Arc<dyn Shell>,
-        _registry: &CommandRegistry,
+        context: &mut Context,
     ) -> Result<Option<Vec<Value>>, ShellError> {
-        call_info.process(&shell, ctrl_c, ls, input)?.run()
+        call_info
+            .process(context, ls, input)?
+            .run()
+            .map_err(|err| err.at(call_info.nth_span(0)))
     }
 }
 
@@ -1,33 +1,133 @@
+use std::convert::TryFrom;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
 use serde::Deserialize;
 
+pub use alias::Alias;
+pub use bg::Bg;
+pub use break_::Break;
+pub use cat::Cat;
 pub use cd::Cd;
+pub use config::ConfigCommand;
+pub use continue_::Continue;
 pub use count::Count;
 pub use cp::Cp;
+pub use def::Def;
+pub use dirs::Dirs;
+pub use each::Each;
+pub use enter::Enter;
 pub use exit::Exit;
+pub use fg::Fg;
+pub use first::First;
+pub use for_::For;
+pub use from_csv::FromCsv;
+pub use from_json::FromJson;
+pub use from_lines::FromLines;
+pub use from_ssv::FromSsv;
+pub use from_toml::FromToml;
+pub use get::Get;
+pub use hash::Hash;
 pub use help::Help;
+pub use history::History;
+pub use if_::If;
+pub use jobs::Jobs;
+pub use kill::Kill;
+pub use last::Last;
 pub use ls::Ls;
 pub use mkdir::Mkdir;
+pub use next::Next;
+pub use open::Open;
+pub use par_each::ParEach;
+pub use popd::Popd;
+pub use prev::Previous;
+pub use pushd::Pushd;
 pub use pwd::Pwd;
+pub use reduce::Reduce;
+pub use rehash::Rehash;
+pub use reverse::Reverse;
+pub use save::Save;
+pub use select::Select;
+pub use shells::Shells;
+pub use skip::Skip;
+pub use sort_by::SortBy;
+pub use to_csv::ToCsv;
+pub use to_json::ToJson;
+pub use to_toml::ToToml;
+pub use try_::Try;
+pub use unalias::Unalias;
+pub use uniq::Uniq;
+pub use where_::Where;
+pub use which::Which;
+pub use while_::While;
+pub use z::Z;
 
-use crate::context::CommandRegistry;
+use crate::context::Context;
 use crate::deserializer::ConfigDeserializer;
 use crate::error::ShellError;
+use crate::evaluate::block::Block;
 use crate::evaluate::{CallInfo, Value};
-use crate::shell::Shell;
+use crate::shell::ShellManager;
 use crate::signature::Signature;
 
+pub mod alias;
+pub mod bg;
+pub mod break_;
+pub mod cat;
 pub mod cd;
 pub mod classified;
+pub mod config;
+pub mod continue_;
 pub mod count;
 pub mod cp;
+pub mod def;
+pub mod dirs;
+pub mod each;
+pub mod enter;
 pub mod exit;
+pub mod fg;
+pub mod first;
+pub mod for_;
+pub mod from_csv;
+pub mod from_json;
+pub mod from_lines;
+pub mod from_ssv;
+pub mod from_toml;
+pub mod get;
+pub mod hash;
 pub mod help;
+pub mod history;
+pub mod if_;
+pub mod jobs;
+pub mod kill;
+pub mod last;
 pub mod ls;
 pub mod mkdir;
+pub mod next;
+pub mod open;
+pub mod par_each;
+pub mod popd;
+pub mod prev;
+pub mod pushd;
 pub mod pwd;
+pub mod reduce;
+pub mod rehash;
+pub mod reverse;
+pub mod save;
+pub mod select;
+pub mod shells;
+pub mod skip;
+pub mod sort_by;
+pub mod to_csv;
+pub mod to_json;
+pub mod to_toml;
+pub mod try_;
+pub mod unalias;
+pub mod uniq;
+pub mod where_;
+pub mod which;
+pub mod while_;
+pub mod z;
 pub trait Command: Send + Sync {
     fn name(&self) -> &str;
 
@@ -41,21 +141,31 @@ pub trait Command: Send + Sync {
         &self,
         call_info: CallInfo,
         input: Option<Vec<Value>>,
-        ctrl_c: Arc<AtomicBool>,
-        shell: Arc<dyn Shell>,
-        registry: &CommandRegistry,
+        context: &mut Context,
     ) -> Result<Option<Vec<Value>>, ShellError>;
 
     fn is_binary(&self) -> bool {
         false
     }
+
+    /// Whether the command takes a list read from a document as it is, instead of its items, eg
+    /// to save it back as a list
+    fn takes_lists(&self) -> bool {
+        false
+    }
+
+    /// Whether the output of the command is made of the values of its input, kept, dropped,
+    /// reordered or changed one by one, so that it is still a list when its input was one
+    fn is_filter(&self) -> bool {
+        false
+    }
 }
 
 pub type BoxedCommand = Arc<dyn Command>;
 
 pub struct RunnableContext {
     pub input: Option<Vec<Value>>,
-    pub shell: Arc<dyn Shell>,
+    pub shell: ShellManager,
     pub ctrl_c: Arc<AtomicBool>,
 }
 
@@ -73,20 +183,73 @@ impl<T> RunnableArgs<T> {
     }
 }
 
+/// A list read from a document, like a JSON array, goes down the pipeline as a single
+/// `Value::List`, so that it is still a list when it is saved even if it has one item. Splits it
+/// into its items, and tells whether it was one.
+pub(crate) fn list_items(input: Option<Vec<Value>>) -> (Option<Vec<Value>>, bool) {
+    match input {
+        Some(values) => match <[Value; 1]>::try_from(values) {
+            Ok([Value::List(items)]) => (Some(items), true),
+            Ok([value]) => (Some(vec![value]), false),
+            Err(values) => (Some(values), false),
+        },
+        None => (None, false),
+    }
+}
+
+/// The values output by a block, with a list read from a document split into its items
+pub(crate) fn output_items(output: Option<Vec<Value>>) -> Vec<Value> {
+    list_items(output).0.unwrap_or_default()
+}
+
+/// Joins textual input, eg the lines printed by an external command, into a single string
+pub(crate) fn input_as_string(input: Option<Vec<Value>>) -> Result<String, ShellError> {
+    let mut out = String::new();
+    for value in input.unwrap_or_default() {
+        match value {
+            Value::Row(_) | Value::List(_) => {
+                return Err(ShellError::type_mismatch(format!(
+                    "text input, found {}",
+                    value.type_name()
+                )));
+            }
+            value => {
+                out.push_str(&value.to_string());
+                if !out.ends_with('\n') {
+                    out.push('\n');
+                }
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Takes the block argument of a command like `each`
+pub(crate) fn expect_block(value: Option<&Value>) -> Result<Block, ShellError> {
+    match value {
+        Some(Value::Block(block)) => Ok(block.clone()),
+        Some(value) => Err(ShellError::type_mismatch(format!(
+            "block, found {}",
+            value.type_name()
+        ))),
+        None => Err(ShellError::type_mismatch("block")),
+    }
+}
+
 impl CallInfo {
     pub(crate) fn process<'de, T: Deserialize<'de>>(
         &self,
-        shell: &Arc<dyn Shell>,
-        ctrl_c: Arc<AtomicBool>,
+        context: &Context,
         callback: CommandCallback<T>,
         input: Option<Vec<Value>>,
     ) -> Result<RunnableArgs<T>, ShellError> {
         let mut deserializer = ConfigDeserializer::from_call_info(self.clone());
+        let args = T::deserialize(&mut deserializer).map_err(|err| err.at(deserializer.span()))?;
         Ok(RunnableArgs {
-            args: T::deserialize(&mut deserializer)?,
+            args,
             context: RunnableContext {
-                shell: shell.clone(),
-                ctrl_c,
+                shell: context.shell.clone(),
+                ctrl_c: context.ctrl_c.clone(),
                 input,
             },
             callback,
@@ -0,0 +1,26 @@
+use crate::commands::Command;
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::{CallInfo, Value};
+
+pub struct Next;
+
+impl Command for Next {
+    fn name(&self) -> &str {
+        "n"
+    }
+
+    fn usage(&self) -> &str {
+        "Go to the next shell."
+    }
+
+    fn run(
+        &self,
+        _call_info: CallInfo,
+        _input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        context.shell.next();
+        Ok(None)
+    }
+}
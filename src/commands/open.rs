@@ -0,0 +1,77 @@
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::commands::{Command, RunnableContext};
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::{CallInfo, Value};
+use crate::format::Format;
+use crate::parser::syntax_shape::SyntaxShape;
+use crate::shell::ShellManager;
+use crate::signature::Signature;
+
+#[derive(Deserialize)]
+pub struct OpenArgs {
+    pub path: PathBuf,
+    pub raw: bool,
+}
+
+pub struct Open;
+
+impl Command for Open {
+    fn name(&self) -> &str {
+        "open"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("open")
+            .required("path", SyntaxShape::Path, "the file to open")
+            .switch("raw", "load content as lines instead of parsing it")
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Load a file into a cell, parsing it according to its extension."
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        call_info
+            .process(context, open, input)?
+            .run()
+            .map_err(|err| err.at(call_info.nth_span(0)))
+    }
+}
+
+fn open(
+    OpenArgs { path, raw }: OpenArgs,
+    ctx: &RunnableContext,
+) -> Result<Option<Vec<Value>>, ShellError> {
+    let path = ctx.shell.cwd().join(path);
+    let contents = read_file(&ctx.shell, &path)?;
+    match Format::from_path(&path) {
+        Some(format) if !raw => match format.parse(&contents)? {
+            Value::List(items) => Ok(Some(vec![Value::List(items)])),
+            value => Ok(Some(vec![value])),
+        },
+        _ => Ok(Some(lines(&contents))),
+    }
+}
+
+pub(crate) fn read_file(shell: &ShellManager, path: &Path) -> Result<String, ShellError> {
+    shell
+        .read_file(path)
+        .map_err(|err| err.with_help("check that the file exists and is readable"))
+}
+
+pub(crate) fn lines(contents: &str) -> Vec<Value> {
+    contents
+        .lines()
+        .map(|line| Value::String(line.to_string()))
+        .collect()
+}
@@ -0,0 +1,65 @@
+use std::sync::atomic::Ordering;
+
+use rayon::prelude::*;
+
+use crate::commands::classified::pipeline::run_block;
+use crate::commands::{expect_block, output_items, Command};
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::{CallInfo, Value};
+use crate::parser::syntax_shape::SyntaxShape;
+use crate::signature::Signature;
+
+pub struct ParEach;
+
+impl Command for ParEach {
+    fn name(&self) -> &str {
+        "par-each"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("par-each")
+            .required(
+                "block",
+                SyntaxShape::Block,
+                "the block to run for each value",
+            )
+            .switch("ignore-errors", "skip the values the block fails on")
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Like each, but runs the blocks on a thread pool. The output keeps the order of the input."
+    }
+
+    fn is_filter(&self) -> bool {
+        true
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        let block = expect_block(call_info.args.nth(0))?;
+        let ignore_errors = call_info.args.has("ignore-errors");
+        // every block gets its own context, they only share the registry, the shell and ctrl-c
+        let outputs = input
+            .unwrap_or_default()
+            .into_par_iter()
+            .map(|value| {
+                if context.ctrl_c.load(Ordering::SeqCst) {
+                    return Ok(None);
+                }
+                let mut context = context.clone();
+                context.scope.insert(block.param(0, "it"), value.clone());
+                match run_block(&block, &mut context, Some(vec![value])) {
+                    Err(err) if ignore_errors && !err.is_loop_control() => Ok(None),
+                    output => output,
+                }
+            })
+            .collect::<Result<Vec<_>, ShellError>>()?;
+        Ok(Some(outputs.into_iter().flat_map(output_items).collect()))
+    }
+}
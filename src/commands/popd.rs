@@ -0,0 +1,29 @@
+use crate::commands::Command;
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::{CallInfo, Value};
+
+pub struct Popd;
+
+impl Command for Popd {
+    fn name(&self) -> &str {
+        "popd"
+    }
+
+    fn usage(&self) -> &str {
+        "Change to the directory on the top of the directory stack and remove it."
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        _input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        context
+            .shell
+            .popd()
+            .map_err(|err| err.at(call_info.nth_span(0)))?;
+        Ok(None)
+    }
+}
@@ -0,0 +1,26 @@
+use crate::commands::Command;
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::{CallInfo, Value};
+
+pub struct Previous;
+
+impl Command for Previous {
+    fn name(&self) -> &str {
+        "p"
+    }
+
+    fn usage(&self) -> &str {
+        "Go to the previous shell."
+    }
+
+    fn run(
+        &self,
+        _call_info: CallInfo,
+        _input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        context.shell.prev();
+        Ok(None)
+    }
+}
@@ -0,0 +1,57 @@
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::commands::{Command, RunnableContext};
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::{CallInfo, Value};
+use crate::parser::syntax_shape::SyntaxShape;
+use crate::signature::Signature;
+
+#[derive(Deserialize)]
+pub struct PushdArgs {
+    pub dst: Option<PathBuf>,
+}
+
+pub struct Pushd;
+
+impl Command for Pushd {
+    fn name(&self) -> &str {
+        "pushd"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("pushd")
+            .optional(
+                "destination",
+                SyntaxShape::Path,
+                "the directory to change to, defaults to the top of the stack",
+            )
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Change to a new path and push the current one on the directory stack."
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        call_info
+            .process(context, pushd, input)?
+            .run()
+            .map_err(|err| err.at(call_info.nth_span(0)))
+    }
+}
+
+fn pushd(
+    PushdArgs { dst }: PushdArgs,
+    ctx: &RunnableContext,
+) -> Result<Option<Vec<Value>>, ShellError> {
+    ctx.shell.pushd(dst)?;
+    Ok(None)
+}
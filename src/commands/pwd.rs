@@ -1,11 +1,7 @@
-use std::sync::atomic::AtomicBool;
-use std::sync::Arc;
-
 use crate::commands::Command;
-use crate::context::CommandRegistry;
+use crate::context::Context;
 use crate::error::ShellError;
 use crate::evaluate::{CallInfo, Value};
-use crate::shell::Shell;
 
 pub struct Pwd;
 
@@ -22,10 +18,8 @@ impl Command for Pwd {
         &self,
         _call_info: CallInfo,
         _input: Option<Vec<Value>>,
-        _ctrl_c: Arc<AtomicBool>,
-        shell: Arc<dyn Shell>,
-        _registry: &CommandRegistry,
+        context: &mut Context,
     ) -> Result<Option<Vec<Value>>, ShellError> {
-        shell.pwd()
+        context.shell.pwd()
     }
 }
@@ -0,0 +1,72 @@
+use std::sync::atomic::Ordering;
+
+use crate::commands::classified::pipeline::run_block;
+use crate::commands::{expect_block, Command};
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::operator::parse_literal;
+use crate::evaluate::{CallInfo, Value};
+use crate::parser::syntax_shape::SyntaxShape;
+use crate::signature::Signature;
+
+pub struct Reduce;
+
+impl Command for Reduce {
+    fn name(&self) -> &str {
+        "reduce"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("reduce")
+            .required(
+                "block",
+                SyntaxShape::Block,
+                "the block computing the next accumulator from $acc and $it",
+            )
+            .named(
+                "fold",
+                SyntaxShape::Any,
+                "the initial accumulator, defaults to the first value",
+            )
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Combine the input values into one by running a block with $acc and $it bound, or the parameters of the block like `{ |sum, x| ... }`."
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        let block = expect_block(call_info.args.nth(0))?;
+        let mut values = input.unwrap_or_default().into_iter();
+        let mut acc = match call_info.args.get("fold") {
+            Some(Value::String(s)) => parse_literal(s),
+            Some(value) => value.clone(),
+            None => match values.next() {
+                Some(value) => value,
+                None => return Ok(Some(vec![])),
+            },
+        };
+        let saved_scope = context.scope.clone();
+        for value in values {
+            if context.ctrl_c.load(Ordering::SeqCst) {
+                break;
+            }
+            context.scope.insert(block.param(0, "acc"), acc);
+            context.scope.insert(block.param(1, "it"), value.clone());
+            let output = run_block(&block, context, Some(vec![value]));
+            context.scope = saved_scope.clone();
+            let mut output = output?.unwrap_or_default();
+            acc = match output.len() {
+                0 => Value::Nothing,
+                1 => output.remove(0),
+                _ => Value::List(output),
+            };
+        }
+        Ok(Some(vec![acc]))
+    }
+}
@@ -0,0 +1,26 @@
+use crate::commands::Command;
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::{CallInfo, Value};
+
+pub struct Rehash;
+
+impl Command for Rehash {
+    fn name(&self) -> &str {
+        "rehash"
+    }
+
+    fn usage(&self) -> &str {
+        "Forget the executables found in PATH, to find those installed since."
+    }
+
+    fn run(
+        &self,
+        _call_info: CallInfo,
+        _input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        context.path_cache.rehash();
+        Ok(None)
+    }
+}
@@ -0,0 +1,29 @@
+use crate::commands::Command;
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::{CallInfo, Value};
+
+pub struct Reverse;
+
+impl Command for Reverse {
+    fn name(&self) -> &str {
+        "reverse"
+    }
+
+    fn usage(&self) -> &str {
+        "Reverse the order of the rows."
+    }
+
+    fn is_filter(&self) -> bool {
+        true
+    }
+
+    fn run(
+        &self,
+        _call_info: CallInfo,
+        input: Option<Vec<Value>>,
+        _context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        Ok(input.map(|values| values.into_iter().rev().collect()))
+    }
+}
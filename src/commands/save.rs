@@ -0,0 +1,89 @@
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::commands::{output_items, Command, RunnableContext};
+use crate::context::Context;
+use crate::error::{ErrorKind, ShellError};
+use crate::evaluate::{CallInfo, Value};
+use crate::format::Format;
+use crate::parser::syntax_shape::SyntaxShape;
+use crate::signature::Signature;
+
+#[derive(Deserialize)]
+pub struct SaveArgs {
+    pub path: Option<PathBuf>,
+    pub raw: bool,
+}
+
+pub struct Save;
+
+impl Command for Save {
+    fn name(&self) -> &str {
+        "save"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("save")
+            .optional(
+                "path",
+                SyntaxShape::Path,
+                "the file to save to, in a value shell defaults to the file it was entered from",
+            )
+            .switch(
+                "raw",
+                "save the values as lines of text, ignoring the extension",
+            )
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Save the input, or the data of a value shell, to a file, serializing it according to its extension."
+    }
+
+    fn takes_lists(&self) -> bool {
+        true
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        call_info
+            .process(context, save, input)?
+            .run()
+            .map_err(|err| err.at(call_info.nth_span(0)))
+    }
+}
+
+fn save(
+    SaveArgs { path, raw }: SaveArgs,
+    ctx: &RunnableContext,
+) -> Result<Option<Vec<Value>>, ShellError> {
+    let path = match path {
+        Some(path) => ctx.shell.cwd().join(path),
+        None => ctx.shell.source().ok_or_else(|| {
+            ShellError::new(ErrorKind::InvalidInput, "Expected the file to save to")
+        })?,
+    };
+    let values = match (ctx.input.clone(), ctx.shell.value()) {
+        (Some(values), _) => values,
+        (None, Some(value)) => vec![value],
+        (None, None) => vec![],
+    };
+    let contents = match Format::from_path(&path) {
+        Some(format) if !raw => format.serialize(values)?,
+        _ => {
+            let mut contents = String::new();
+            for value in output_items(Some(values)) {
+                contents.push_str(value.to_string().trim_end_matches('\n'));
+                contents.push('\n');
+            }
+            contents
+        }
+    };
+    ctx.shell.write_file(&path, &contents)?;
+    Ok(None)
+}
@@ -0,0 +1,67 @@
+use serde::Deserialize;
+
+use crate::commands::{Command, RunnableContext};
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::dict::Dictionary;
+use crate::evaluate::{CallInfo, Value};
+use crate::parser::syntax_shape::SyntaxShape;
+use crate::signature::Signature;
+
+#[derive(Deserialize)]
+pub struct SelectArgs {
+    pub rest: Vec<String>,
+}
+
+pub struct Select;
+
+impl Command for Select {
+    fn name(&self) -> &str {
+        "select"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("select")
+            .rest(SyntaxShape::String, "the columns to keep")
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Keep only the given columns of each row."
+    }
+
+    fn is_filter(&self) -> bool {
+        true
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        call_info.process(context, select, input)?.run()
+    }
+}
+
+fn select(
+    SelectArgs { rest: columns }: SelectArgs,
+    ctx: &RunnableContext,
+) -> Result<Option<Vec<Value>>, ShellError> {
+    let values = ctx.input.clone().unwrap_or_default();
+    let rows = values
+        .iter()
+        .map(|value| {
+            let mut row = Dictionary::default();
+            for column in columns.iter() {
+                let cell = value
+                    .get_data_by_column_path(column)
+                    .cloned()
+                    .unwrap_or(Value::Nothing);
+                row.insert(column.clone(), cell);
+            }
+            Value::Row(row)
+        })
+        .collect();
+    Ok(Some(rows))
+}
@@ -0,0 +1,41 @@
+use crate::commands::Command;
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::dict::Dictionary;
+use crate::evaluate::{CallInfo, Value};
+
+pub struct Shells;
+
+impl Command for Shells {
+    fn name(&self) -> &str {
+        "shells"
+    }
+
+    fn usage(&self) -> &str {
+        "Display the list of current shells."
+    }
+
+    fn run(
+        &self,
+        _call_info: CallInfo,
+        _input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        let current = context.shell.current_index();
+        Ok(Some(
+            context
+                .shell
+                .list()
+                .into_iter()
+                .enumerate()
+                .map(|(index, (name, path))| {
+                    let mut row = Dictionary::default();
+                    row.insert("active", Value::Boolean(index == current));
+                    row.insert("name", Value::String(name));
+                    row.insert("path", Value::String(path));
+                    Value::Row(row)
+                })
+                .collect(),
+        ))
+    }
+}
@@ -0,0 +1,56 @@
+use serde::Deserialize;
+
+use crate::commands::{Command, RunnableContext};
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::{CallInfo, Value};
+use crate::parser::syntax_shape::SyntaxShape;
+use crate::signature::Signature;
+
+#[derive(Deserialize)]
+pub struct SkipArgs {
+    pub rows: Option<usize>,
+}
+
+pub struct Skip;
+
+impl Command for Skip {
+    fn name(&self) -> &str {
+        "skip"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("skip")
+            .optional(
+                "rows",
+                SyntaxShape::Int,
+                "the number of rows to skip, defaults to 1",
+            )
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Skip the first rows of the input."
+    }
+
+    fn is_filter(&self) -> bool {
+        true
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        call_info.process(context, skip, input)?.run()
+    }
+}
+
+fn skip(
+    SkipArgs { rows }: SkipArgs,
+    ctx: &RunnableContext,
+) -> Result<Option<Vec<Value>>, ShellError> {
+    let values = ctx.input.clone().unwrap_or_default();
+    Ok(Some(values.into_iter().skip(rows.unwrap_or(1)).collect()))
+}
@@ -0,0 +1,79 @@
+use serde::Deserialize;
+
+use crate::commands::{Command, RunnableContext};
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::operator::compare_values;
+use crate::evaluate::{CallInfo, Value};
+use crate::parser::syntax_shape::SyntaxShape;
+use crate::signature::Signature;
+
+#[derive(Deserialize)]
+pub struct SortByArgs {
+    pub rest: Vec<String>,
+    pub reverse: bool,
+}
+
+pub struct SortBy;
+
+impl Command for SortBy {
+    fn name(&self) -> &str {
+        "sort-by"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("sort-by")
+            .rest(SyntaxShape::String, "the column(s) to sort by")
+            .switch("reverse", "sort in descending order")
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Sort by the given columns, or by the values themselves if no column is given."
+    }
+
+    fn is_filter(&self) -> bool {
+        true
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        call_info.process(context, sort_by, input)?.run()
+    }
+}
+
+fn sort_by(
+    SortByArgs {
+        rest: columns,
+        reverse,
+    }: SortByArgs,
+    ctx: &RunnableContext,
+) -> Result<Option<Vec<Value>>, ShellError> {
+    let mut values = ctx.input.clone().unwrap_or_default();
+    values.sort_by(|a, b| {
+        if columns.is_empty() {
+            return compare_values(a, b).unwrap_or_else(|| a.cmp(b));
+        }
+        for column in columns.iter() {
+            let ordering = match (
+                a.get_data_by_column_path(column),
+                b.get_data_by_column_path(column),
+            ) {
+                (Some(a), Some(b)) => compare_values(a, b).unwrap_or_else(|| a.cmp(b)),
+                (a, b) => a.is_some().cmp(&b.is_some()),
+            };
+            if ordering != std::cmp::Ordering::Equal {
+                return ordering;
+            }
+        }
+        std::cmp::Ordering::Equal
+    });
+    if reverse {
+        values.reverse();
+    }
+    Ok(Some(values))
+}
@@ -0,0 +1,57 @@
+use serde::Deserialize;
+
+use crate::commands::from_csv::separator_byte;
+use crate::commands::{Command, RunnableContext};
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::{CallInfo, Value};
+use crate::format::csv::values_to_csv_string;
+use crate::parser::syntax_shape::SyntaxShape;
+use crate::signature::Signature;
+
+#[derive(Deserialize)]
+pub struct ToCsvArgs {
+    pub separator: Option<String>,
+}
+
+pub struct ToCsv;
+
+impl Command for ToCsv {
+    fn name(&self) -> &str {
+        "to-csv"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("to-csv")
+            .named(
+                "separator",
+                SyntaxShape::String,
+                "a character to separate columns, defaults to ','",
+            )
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Convert the piped rows to CSV text."
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        call_info.process(context, to_csv, input)?.run()
+    }
+}
+
+fn to_csv(
+    ToCsvArgs { separator }: ToCsvArgs,
+    ctx: &RunnableContext,
+) -> Result<Option<Vec<Value>>, ShellError> {
+    let separator = separator_byte(separator, b',')?;
+    let values = ctx.input.clone().unwrap_or_default();
+    Ok(Some(vec![Value::String(values_to_csv_string(
+        &values, separator,
+    )?)]))
+}
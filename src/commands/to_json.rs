@@ -0,0 +1,59 @@
+use serde::Deserialize;
+
+use crate::commands::{Command, RunnableContext};
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::{CallInfo, Value};
+use crate::format::json::value_to_json_string;
+use crate::signature::Signature;
+
+#[derive(Deserialize)]
+pub struct ToJsonArgs {
+    pub pretty: bool,
+}
+
+pub struct ToJson;
+
+impl Command for ToJson {
+    fn name(&self) -> &str {
+        "to-json"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("to-json")
+            .switch("pretty", "indent the output")
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Convert the piped values to JSON text."
+    }
+
+    fn takes_lists(&self) -> bool {
+        true
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        call_info.process(context, to_json, input)?.run()
+    }
+}
+
+fn to_json(
+    ToJsonArgs { pretty }: ToJsonArgs,
+    ctx: &RunnableContext,
+) -> Result<Option<Vec<Value>>, ShellError> {
+    let mut values = ctx.input.clone().unwrap_or_default();
+    let value = if values.len() == 1 {
+        values.remove(0)
+    } else {
+        Value::List(values)
+    };
+    Ok(Some(vec![Value::String(value_to_json_string(
+        &value, pretty,
+    )?)]))
+}
@@ -0,0 +1,35 @@
+use crate::commands::Command;
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::{CallInfo, Value};
+use crate::format::toml::value_to_toml_string;
+
+pub struct ToToml;
+
+impl Command for ToToml {
+    fn name(&self) -> &str {
+        "to-toml"
+    }
+
+    fn usage(&self) -> &str {
+        "Convert the piped row to TOML text."
+    }
+
+    fn run(
+        &self,
+        _call_info: CallInfo,
+        input: Option<Vec<Value>>,
+        _context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        let mut values = input.unwrap_or_default();
+        if values.len() != 1 {
+            return Err(ShellError::runtime_error(format!(
+                "to-toml expects exactly one row, found {}",
+                values.len()
+            )));
+        }
+        Ok(Some(vec![Value::String(value_to_toml_string(
+            &values.remove(0),
+        )?)]))
+    }
+}
@@ -0,0 +1,106 @@
+use crate::commands::classified::pipeline::run_block;
+use crate::commands::{expect_block, Command};
+use crate::context::Context;
+use crate::error::{ProximateShellError, ShellError};
+use crate::evaluate::dict::Dictionary;
+use crate::evaluate::{CallInfo, Value};
+use crate::parser::syntax_shape::SyntaxShape;
+use crate::signature::Signature;
+
+pub struct Try;
+
+impl Command for Try {
+    fn name(&self) -> &str {
+        "try"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("try")
+            .rest(
+                SyntaxShape::Any,
+                "the block to try, then optionally catch and the block to run on failure",
+            )
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Run a block and catch its errors, eg `try { open x.json } catch { |err| echo $err.message }`. The error is bound to $err, or to the parameter of the catch block, and is also its input."
+    }
+
+    fn takes_lists(&self) -> bool {
+        true
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        let args = call_info.args.slice_from(0);
+        let (body, handler) = match &args[..] {
+            [body] => (expect_block(Some(body))?, None),
+            [body, Value::String(catch), handler] if catch == "catch" => (
+                expect_block(Some(body))?,
+                Some(expect_block(Some(handler))?),
+            ),
+            _ => {
+                return Err(ShellError::runtime_error(
+                    "try needs a block, optionally followed by catch and another block",
+                )
+                .at(call_info.span_from(0)))
+            }
+        };
+        let err = match run_block(&body, context, input) {
+            Ok(output) => return Ok(output),
+            Err(err) if err.is_loop_control() => return Err(err),
+            Err(err) => err,
+        };
+        let handler = match handler {
+            Some(handler) => handler,
+            None => return Ok(None),
+        };
+        let value = error_value(&err, &body.source);
+        let saved_scope = context.scope.clone();
+        context.scope.insert(handler.param(0, "err"), value.clone());
+        let output = run_block(&handler, context, Some(vec![value]));
+        context.scope = saved_scope;
+        output
+    }
+}
+
+/// Describes an error as a row with its message, its kind, the span it points at and the text
+/// there, and the message of its cause
+fn error_value(err: &ShellError, source: &str) -> Value {
+    let source = err.details.source.as_deref().unwrap_or(source);
+    let kind = match (&err.error, err.kind()) {
+        (_, Some(kind)) => kind.name(),
+        (ProximateShellError::ParseError(..), _) => "parse",
+        _ => "other",
+    };
+    let mut row = Dictionary::default();
+    row.insert("message", Value::String(err.to_string()));
+    row.insert("kind", Value::String(kind.to_string()));
+    match err.span() {
+        Some(span) => {
+            let mut span_row = Dictionary::default();
+            span_row.insert("start", Value::Int(span.start().into()));
+            span_row.insert("end", Value::Int(span.end().into()));
+            row.insert("span", Value::Row(span_row));
+            let text = source.get(span.start()..span.end()).unwrap_or_default();
+            row.insert("text", Value::String(text.to_string()));
+        }
+        None => {
+            row.insert("span", Value::Nothing);
+            row.insert("text", Value::Nothing);
+        }
+    }
+    row.insert(
+        "cause",
+        match &err.cause {
+            Some(cause) => Value::String(cause.to_string()),
+            None => Value::Nothing,
+        },
+    );
+    Value::Row(row)
+}
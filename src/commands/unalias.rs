@@ -0,0 +1,41 @@
+use crate::commands::Command;
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::{CallInfo, Value};
+use crate::parser::syntax_shape::SyntaxShape;
+use crate::signature::Signature;
+
+pub struct Unalias;
+
+impl Command for Unalias {
+    fn name(&self) -> &str {
+        "unalias"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("unalias")
+            .rest(SyntaxShape::String, "the aliases to remove")
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Remove aliases."
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        _input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        for name in call_info.args.positional_iter().map(Value::to_string) {
+            if context.aliases.remove(&name).is_none() {
+                return Err(ShellError::runtime_error(format!(
+                    "Unknown alias: {}",
+                    name
+                )));
+            }
+        }
+        Ok(None)
+    }
+}
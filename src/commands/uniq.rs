@@ -0,0 +1,37 @@
+use indexmap::IndexSet;
+
+use crate::commands::Command;
+use crate::context::Context;
+use crate::error::ShellError;
+use crate::evaluate::{CallInfo, Value};
+
+pub struct Uniq;
+
+impl Command for Uniq {
+    fn name(&self) -> &str {
+        "uniq"
+    }
+
+    fn usage(&self) -> &str {
+        "Remove duplicate rows, keeping the first occurrence."
+    }
+
+    fn is_filter(&self) -> bool {
+        true
+    }
+
+    fn run(
+        &self,
+        _call_info: CallInfo,
+        input: Option<Vec<Value>>,
+        _context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        Ok(input.map(|values| {
+            values
+                .into_iter()
+                .collect::<IndexSet<_>>()
+                .into_iter()
+                .collect()
+        }))
+    }
+}
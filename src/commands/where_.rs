@@ -0,0 +1,72 @@
+use crate::commands::Command;
+use crate::context::Context;
+use crate::error::{ErrorKind, ShellError};
+use crate::evaluate::operator::{parse_literal, Operator};
+use crate::evaluate::{CallInfo, Value};
+use crate::parser::syntax_shape::SyntaxShape;
+use crate::signature::Signature;
+
+pub struct Where;
+
+impl Command for Where {
+    fn name(&self) -> &str {
+        "where"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("where")
+            .required("column", SyntaxShape::String, "the column to test")
+            .required(
+                "operator",
+                SyntaxShape::String,
+                "one of ==, !=, <, >, <=, >=, =~ (contains), !~ (doesn't contain)",
+            )
+            .required("value", SyntaxShape::Any, "the value to compare with")
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Filter rows by a condition, eg `where size > 10kb`."
+    }
+
+    fn is_filter(&self) -> bool {
+        true
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        input: Option<Vec<Value>>,
+        _context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        let args = &call_info.args;
+        let column = args.nth(0).map(Value::to_string).unwrap_or_default();
+        let operator = args.nth(1).map(Value::to_string).unwrap_or_default();
+        let operator = Operator::parse(&operator)
+            .ok_or_else(|| ShellError::runtime_error(format!("Unknown operator: {}", operator)))?;
+        let right = match args.nth(2) {
+            Some(Value::String(s)) => parse_literal(s),
+            Some(Value::Block(_)) => {
+                return Err(
+                    ShellError::new(ErrorKind::TypeMismatch, "Can't compare with a block")
+                        .at(call_info.nth_span(2)),
+                )
+            }
+            Some(value) => value.clone(),
+            None => Value::Nothing,
+        };
+        // rows without the column are left out, and so are those whose column can't be compared
+        // with the value, except by `!=` as such a column isn't equal to it
+        let results = input
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|value| match value.get_data_by_column_path(&column) {
+                Some(left) => operator
+                    .apply(left, &right)
+                    .unwrap_or(operator == Operator::NotEqual),
+                None => false,
+            })
+            .collect();
+        Ok(Some(results))
+    }
+}
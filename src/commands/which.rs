@@ -0,0 +1,92 @@
+use crate::commands::Command;
+use crate::context::Context;
+use crate::error::{ErrorKind, ShellError};
+use crate::evaluate::dict::Dictionary;
+use crate::evaluate::{CallInfo, Value};
+use crate::parser::syntax_shape::SyntaxShape;
+use crate::signature::Signature;
+
+pub struct Which;
+
+impl Command for Which {
+    fn name(&self) -> &str {
+        "which"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("which")
+            .rest(SyntaxShape::String, "the names of the commands")
+            .switch(
+                "all",
+                "list everything the name stands for, not only what runs",
+            )
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Show whether a name runs an alias, an internal command or an executable on PATH."
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        _input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        if call_info.args.is_empty() {
+            return Err(
+                ShellError::new(ErrorKind::InvalidInput, "Expected the name of a command")
+                    .at(call_info.name_span),
+            );
+        }
+        let all = call_info.args.has("all");
+        let cwd = context.shell.cwd();
+        let mut results = vec![];
+        for (pos, name) in call_info.args.slice_from(0).iter().enumerate() {
+            let name = name.to_string();
+            let mut found = vec![];
+            if let Some(expansion) = context.aliases.get(&name) {
+                found.push(("alias", Value::String(expansion)));
+            }
+            if context.registry.has(&name) {
+                found.push(("internal", Value::Nothing));
+            }
+            let paths = if all {
+                context.path_cache.resolve_all(&name)
+            } else {
+                context
+                    .path_cache
+                    .resolve(&name, &cwd)
+                    .into_iter()
+                    .collect()
+            };
+            for path in paths {
+                found.push((
+                    "external",
+                    Value::String(path.to_string_lossy().to_string()),
+                ));
+            }
+            if found.is_empty() {
+                return Err(ShellError::new(
+                    ErrorKind::NotFound,
+                    format!(
+                        "{} is not an alias, a command or an executable on PATH",
+                        name
+                    ),
+                )
+                .at(call_info.nth_span(pos)));
+            }
+            if !all {
+                found.truncate(1);
+            }
+            for (kind, path) in found {
+                let mut row = Dictionary::default();
+                row.insert("name", Value::String(name.clone()));
+                row.insert("type", Value::String(kind.to_string()));
+                row.insert("path", path);
+                results.push(Value::Row(row));
+            }
+        }
+        Ok(Some(results))
+    }
+}
@@ -0,0 +1,71 @@
+use std::sync::atomic::Ordering;
+
+use crate::commands::classified::pipeline::run_block;
+use crate::commands::if_::condition;
+use crate::commands::{expect_block, output_items, Command};
+use crate::context::Context;
+use crate::error::{ProximateShellError, ShellError};
+use crate::evaluate::{CallInfo, Value};
+use crate::parser::syntax_shape::SyntaxShape;
+use crate::signature::Signature;
+
+pub struct While;
+
+impl Command for While {
+    fn name(&self) -> &str {
+        "while"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("while")
+            .rest(
+                SyntaxShape::Any,
+                "the condition in a block followed by the block to run",
+            )
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Run a block as long as a condition holds, eg `while { ls | where name == lock | count } { sleep 1 }`."
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        _input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        let mut args = call_info.args.slice_from(0);
+        let block = expect_block(args.pop().as_ref())?;
+        // the arguments are evaluated once, only a block is evaluated again on every iteration
+        if !matches!(args.as_slice(), [Value::Block(_)]) {
+            let span = match args.len() {
+                0 => call_info.name_span,
+                len => call_info.nth_span(0).until(call_info.nth_span(len - 1)),
+            };
+            return Err(
+                ShellError::type_mismatch("a block as the condition of while")
+                    .at(span)
+                    .with_help("put the condition in a block, eg `while { $x < 3 } { ... }`"),
+            );
+        }
+        let mut results = vec![];
+        loop {
+            if context.ctrl_c.load(Ordering::SeqCst) {
+                break;
+            }
+            if !condition(&args, context)? {
+                break;
+            }
+            match run_block(&block, context, None) {
+                Ok(output) => results.extend(output_items(output)),
+                Err(err) => match err.error {
+                    ProximateShellError::Break => break,
+                    ProximateShellError::Continue => continue,
+                    _ => return Err(err),
+                },
+            }
+        }
+        Ok(Some(results))
+    }
+}
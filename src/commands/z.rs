@@ -0,0 +1,101 @@
+use bigdecimal::{BigDecimal, FromPrimitive};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::commands::cd::CdArgs;
+use crate::commands::{Command, RunnableContext};
+use crate::context::Context;
+use crate::error::{ErrorKind, ShellError};
+use crate::evaluate::dict::Dictionary;
+use crate::evaluate::{CallInfo, Value};
+use crate::frecency;
+use crate::parser::syntax_shape::SyntaxShape;
+use crate::signature::Signature;
+
+#[derive(Deserialize)]
+pub struct ZArgs {
+    pub rest: Vec<String>,
+    pub list: bool,
+}
+
+pub struct Z;
+
+impl Command for Z {
+    fn name(&self) -> &str {
+        "z"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("z")
+            .rest(
+                SyntaxShape::String,
+                "parts of the directory's path, in order, ignoring case",
+            )
+            .switch(
+                "list",
+                "list the matching directories with their scores instead",
+            )
+            .desc(self.usage())
+    }
+
+    fn usage(&self) -> &str {
+        "Jump to the directory visited most often and most recently that matches the given parts."
+    }
+
+    fn run(
+        &self,
+        call_info: CallInfo,
+        input: Option<Vec<Value>>,
+        context: &mut Context,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        call_info
+            .process(context, z, input)?
+            .run()
+            .map_err(|err| err.at(call_info.nth_span(0)))
+    }
+}
+
+fn z(
+    ZArgs {
+        rest: fragments,
+        list,
+    }: ZArgs,
+    ctx: &RunnableContext,
+) -> Result<Option<Vec<Value>>, ShellError> {
+    if list {
+        let now = frecency::now();
+        return Ok(Some(
+            frecency::query(&fragments)
+                .into_iter()
+                .map(|record| {
+                    let score = BigDecimal::from_f64(record.score(now)).unwrap_or_default();
+                    let mut row = Dictionary::default();
+                    row.insert(
+                        "path",
+                        Value::String(record.path.to_string_lossy().to_string()),
+                    );
+                    row.insert("score", Value::Number(score.with_scale(2)));
+                    row.insert(
+                        "visited",
+                        Value::Date(DateTime::<Utc>::from(record.visited())),
+                    );
+                    Value::Row(row)
+                })
+                .collect(),
+        ));
+    }
+    if fragments.is_empty() {
+        return ctx.shell.cd(CdArgs { dst: None });
+    }
+    match frecency::best(&fragments, &ctx.shell.cwd()) {
+        Some(path) => ctx.shell.cd(CdArgs { dst: Some(path) }),
+        None => Err(ShellError::new(
+            ErrorKind::NotFound,
+            format!(
+                "No directory visited before matches {}",
+                fragments.join(" ")
+            ),
+        )
+        .with_help("directories are remembered when changed to with `cd`")),
+    }
+}
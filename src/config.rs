@@ -0,0 +1,243 @@
+use std::path::PathBuf;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::cli::{colors, keybindings};
+use crate::error::{ErrorKind, ShellError};
+use crate::evaluate::Value;
+use crate::format::toml::{convert_toml_value_to_value, value_to_toml_value};
+
+/// The settings read from `config.toml` in the config directory. Missing keys take their default
+/// value, so an empty or partial file is fine.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// The number of lines kept in the history
+    pub history_size: usize,
+    /// The key bindings of the line editor, `emacs` or `vi`
+    pub edit_mode: String,
+    /// `audible`, `visible` or `none`
+    pub bell_style: String,
+    /// How completions are shown, `circular` or `list`
+    pub completion_type: String,
+    /// Typing the path of a directory changes to it, like `cd`
+    pub auto_cd: bool,
+    /// What an argument of an external command becomes when it is a glob matching no file,
+    /// `error` or `literal` to pass it as it is
+    pub glob_no_match: String,
+    /// The prompt, `{cwd}` and `{user}` are replaced by the current directory and the user name,
+    /// `{shell}` by the position of the active shell when there is more than one. Without
+    /// `{shell}` the position goes in front.
+    pub prompt: String,
+    pub theme: Theme,
+    /// Flags added to every call of a command, eg `ls = "--all"`
+    pub default_flags: IndexMap<String, String>,
+    /// Key bindings added to the edit mode, eg `ctrl-t = "command:ls | get name"`
+    pub keybindings: IndexMap<String, String>,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            history_size: 1000,
+            edit_mode: "emacs".to_string(),
+            bell_style: "audible".to_string(),
+            completion_type: "circular".to_string(),
+            auto_cd: false,
+            glob_no_match: "error".to_string(),
+            prompt: "{cwd}> ".to_string(),
+            theme: Theme::default(),
+            default_flags: IndexMap::new(),
+            keybindings: default_keybindings(),
+        }
+    }
+}
+
+/// Move over a whole word with Ctrl+ArrowLeft and Ctrl+ArrowRight
+fn default_keybindings() -> IndexMap<String, String> {
+    let mut bindings = IndexMap::new();
+    bindings.insert("ctrl-left".to_string(), "backward-word".to_string());
+    bindings.insert("ctrl-right".to_string(), "forward-word".to_string());
+    bindings
+}
+
+/// The colors of the shell by name, eg `green`, or `none` for no color
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    pub prompt: String,
+    pub error: String,
+    pub header: String,
+    pub index: String,
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme {
+            prompt: "green".to_string(),
+            error: "red".to_string(),
+            header: "green".to_string(),
+            index: "green".to_string(),
+        }
+    }
+}
+
+impl Theme {
+    pub fn prompt(&self) -> &'static str {
+        colors::from_name(&self.prompt).unwrap_or(colors::GREEN)
+    }
+
+    pub fn error(&self) -> &'static str {
+        colors::from_name(&self.error).unwrap_or(colors::RED)
+    }
+
+    pub fn header(&self) -> &'static str {
+        colors::from_name(&self.header).unwrap_or(colors::GREEN)
+    }
+
+    pub fn index(&self) -> &'static str {
+        colors::from_name(&self.index).unwrap_or(colors::GREEN)
+    }
+}
+
+/// The directory holding the config file, the rc script and the history
+pub fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("shell"))
+}
+
+pub fn config_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("config.toml"))
+}
+
+/// The script of shell commands run at startup, one pipeline per line
+pub fn rc_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("rc"))
+}
+
+pub fn history_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("history.txt"))
+}
+
+impl Config {
+    /// Reads the config file, a missing file gives the default config
+    pub fn load() -> Result<Config, ShellError> {
+        let path = match config_path() {
+            Some(path) if path.exists() => path,
+            _ => return Ok(Config::default()),
+        };
+        let contents = std::fs::read_to_string(&path)?;
+        toml::from_str(&contents).map_err(|e| {
+            ShellError::runtime_error(format!("Could not read {}: {}", path.display(), e))
+        })
+    }
+
+    /// Writes the config file, creating the config directory if needed
+    pub fn save(&self) -> Result<(), ShellError> {
+        let path = config_path()
+            .ok_or_else(|| ShellError::runtime_error("Could not find the config directory"))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let contents = toml::to_string_pretty(self)
+            .map_err(|e| ShellError::runtime_error(format!("Could not save config: {}", e)))?;
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// The whole config as a row
+    pub fn to_value(&self) -> Result<Value, ShellError> {
+        Ok(convert_toml_value_to_value(self.to_toml()?))
+    }
+
+    /// Gets a setting by its dotted path, eg `theme.prompt`
+    pub fn get(&self, key: &str) -> Result<Value, ShellError> {
+        self.to_value()?
+            .get_data_by_column_path(key)
+            .cloned()
+            .ok_or_else(|| unknown_setting(key))
+    }
+
+    /// Sets a setting by its dotted path. The value must have the type of the setting.
+    pub fn set(&mut self, key: &str, value: &Value) -> Result<(), ShellError> {
+        let mut config = self.to_toml()?;
+        let mut members = key.split('.').peekable();
+        let mut current = &mut config;
+        while let Some(member) = members.next() {
+            let table = current.as_table_mut().ok_or_else(|| unknown_setting(key))?;
+            if members.peek().is_none() {
+                table.insert(member.to_string(), value_to_toml_value(value)?);
+                break;
+            }
+            current = table
+                .entry(member.to_string())
+                .or_insert_with(|| toml::Value::Table(toml::map::Map::new()));
+        }
+        let config: Config = config
+            .try_into()
+            .map_err(|e| ShellError::runtime_error(format!("Invalid value for {}: {}", key, e)))?;
+        // keys that aren't settings are dropped when converting back
+        config.get(key)?;
+        config.validate(key, &value.to_string())?;
+        *self = config;
+        Ok(())
+    }
+
+    /// The key bindings that can't be parsed, with the reason
+    pub fn invalid_keybindings(&self) -> Vec<ShellError> {
+        self.keybindings
+            .iter()
+            .filter_map(|(key, action)| {
+                self.validate(&format!("keybindings.{}", key), action).err()
+            })
+            .collect()
+    }
+
+    /// Checks the settings that are names, the config file itself only checks the types
+    fn validate(&self, key: &str, value: &str) -> Result<(), ShellError> {
+        let valid = match key {
+            "edit_mode" => keybindings::edit_mode(value).is_some(),
+            "bell_style" => keybindings::bell_style(value).is_some(),
+            "completion_type" => keybindings::completion_type(value).is_some(),
+            _ if key.starts_with("theme.") => colors::from_name(value).is_some(),
+            _ if key.starts_with("keybindings.") => {
+                let name = &key["keybindings.".len()..];
+                if keybindings::parse_key(name).is_none() {
+                    return Err(ShellError::runtime_error(format!("Unknown key: {}", name)));
+                }
+                keybindings::parse_cmd(value).is_some()
+            }
+            _ => true,
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(ShellError::runtime_error(format!(
+                "Invalid value for {}: {}",
+                key, value
+            )))
+        }
+    }
+
+    /// Renders the prompt template for the given directory and shell label
+    pub fn prompt(&self, cwd: &str, shell: &str) -> String {
+        let user = std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .unwrap_or_default();
+        let prompt = self.prompt.replace("{cwd}", cwd).replace("{user}", &user);
+        if self.prompt.contains("{shell}") {
+            prompt.replace("{shell}", shell)
+        } else {
+            format!("{}{}", shell, prompt)
+        }
+    }
+
+    fn to_toml(&self) -> Result<toml::Value, ShellError> {
+        toml::Value::try_from(self)
+            .map_err(|e| ShellError::runtime_error(format!("Could not convert config: {}", e)))
+    }
+}
+
+fn unknown_setting(key: &str) -> ShellError {
+    ShellError::new(ErrorKind::NotFound, format!("Unknown setting: {}", key))
+}
@@ -5,11 +5,15 @@ use indexmap::IndexMap;
 use parking_lot::Mutex;
 
 use crate::commands::BoxedCommand;
+use crate::config::Config;
 use crate::error::ShellError;
 use crate::evaluate::call_info::CallInfo;
+use crate::evaluate::scope::Scope;
 use crate::evaluate::{evaluate_args, Value};
-use crate::parser::hir::Call;
-use crate::shell::{FilesystemShell, Shell};
+use crate::jobs::Jobs;
+use crate::parser::hir::{Call, NamedValue};
+use crate::path_cache::PathCache;
+use crate::shell::ShellManager;
 use crate::signature::Signature;
 
 #[derive(Clone, Default)]
@@ -52,21 +56,62 @@ impl CommandRegistry {
     }
 }
 
+/// The aliases defined with `alias`, shared by every clone of the context like the registry
+#[derive(Clone, Default)]
+pub struct Aliases {
+    aliases: Arc<Mutex<IndexMap<String, String>>>,
+}
+
+impl Aliases {
+    /// Gets the text an alias expands to
+    pub fn get(&self, name: &str) -> Option<String> {
+        self.aliases.lock().get(name).cloned()
+    }
+
+    pub(crate) fn insert(&self, name: impl Into<String>, expansion: impl Into<String>) {
+        self.aliases.lock().insert(name.into(), expansion.into());
+    }
+
+    pub(crate) fn remove(&self, name: &str) -> Option<String> {
+        self.aliases.lock().shift_remove(name)
+    }
+
+    /// All aliases with their expansions, in the order they were defined
+    pub fn list(&self) -> Vec<(String, String)> {
+        self.aliases
+            .lock()
+            .iter()
+            .map(|(name, expansion)| (name.clone(), expansion.clone()))
+            .collect()
+    }
+}
+
 #[derive(Clone)]
 pub struct Context {
     pub registry: CommandRegistry,
     pub current_errors: Arc<Mutex<Vec<ShellError>>>,
     pub ctrl_c: Arc<AtomicBool>,
-    pub(crate) shell: Arc<dyn Shell>,
+    pub(crate) shell: ShellManager,
+    pub scope: Scope,
+    pub aliases: Aliases,
+    pub config: Arc<Mutex<Config>>,
+    pub jobs: Jobs,
+    pub path_cache: PathCache,
 }
 
 impl Context {
     pub fn basic() -> Self {
+        let ctrl_c = Arc::new(AtomicBool::new(false));
         Self {
             registry: CommandRegistry::empty(),
             current_errors: Arc::new(Mutex::new(Vec::new())),
-            ctrl_c: Arc::new(AtomicBool::new(false)),
-            shell: Arc::new(FilesystemShell::new()),
+            ctrl_c: ctrl_c.clone(),
+            shell: ShellManager::basic(),
+            scope: Scope::new(),
+            aliases: Aliases::default(),
+            config: Arc::new(Mutex::new(Config::default())),
+            jobs: Jobs::new(ctrl_c),
+            path_cache: PathCache::default(),
         }
     }
     pub fn add_commands(&mut self, commands: Vec<BoxedCommand>) {
@@ -89,15 +134,41 @@ impl Context {
         source: &str,
         input: Option<Vec<Value>>,
     ) -> Result<Option<Vec<Value>>, ShellError> {
+        let name_span = args.head.span;
+        let positional_spans = args
+            .positional
+            .iter()
+            .flatten()
+            .map(|token| token.span)
+            .collect();
+        let named_spans = args
+            .named
+            .iter()
+            .flat_map(|named| named.iter())
+            .filter_map(|(name, value)| match value {
+                NamedValue::PresentSwitch(span) => Some((name.clone(), *span)),
+                NamedValue::Value(token) => Some((name.clone(), token.span)),
+                NamedValue::AbsentSwitch | NamedValue::AbsentValue => None,
+            })
+            .collect();
         let call_info = CallInfo {
-            args: evaluate_args(args, command.clone(), &self.registry, source)?,
+            args: evaluate_args(
+                args.clone(),
+                command.clone(),
+                &self.registry,
+                &self.scope,
+                source,
+            )
+            .map_err(|err| err.with_source(source))?,
+            name_span,
+            span: args.span,
+            source: source.to_string(),
+            positional_spans,
+            named_spans,
         };
-        command.run(
-            call_info,
-            input,
-            self.ctrl_c.clone(),
-            self.shell.clone(),
-            &self.registry,
-        )
+        // errors that don't point at an argument point at the command
+        command
+            .run(call_info, input, self)
+            .map_err(|err| err.at(name_span).with_source(source))
     }
 }
@@ -7,6 +7,7 @@ use serde::de;
 use crate::error::ShellError;
 use crate::evaluate::CallInfo;
 use crate::evaluate::Value;
+use crate::parser::span::Span;
 
 #[derive(Debug)]
 pub struct DeserializerItem<'de> {
@@ -19,6 +20,8 @@ pub struct ConfigDeserializer<'de> {
     stack: Vec<DeserializerItem<'de>>,
     saw_root: bool,
     position: usize,
+    /// The span of the argument being deserialized, errors point at it
+    span: Span,
 }
 
 impl<'de> ConfigDeserializer<'de> {
@@ -28,6 +31,7 @@ impl<'de> ConfigDeserializer<'de> {
             stack: vec![],
             saw_root: false,
             position: 0,
+            span: Span::unknown(),
         }
     }
 
@@ -41,13 +45,16 @@ impl<'de> ConfigDeserializer<'de> {
     pub fn push(&mut self, name: &'static str) -> Result<(), ShellError> {
         let value: Option<Value> = if name == "rest" {
             let positional = self.call.args.slice_from(self.position);
+            self.span = self.call.span_from(self.position);
             self.position += positional.len();
             Some(Value::List(positional))
         } else if self.call.args.has(name) {
+            self.span = self.call.named_span(name);
             self.call.args.get(name).cloned()
         } else {
             let position = self.position;
             self.position += 1;
+            self.span = self.call.nth_span(position);
             self.call.args.nth(position).cloned()
         };
 
@@ -59,6 +66,11 @@ impl<'de> ConfigDeserializer<'de> {
         Ok(())
     }
 
+    /// The span of the argument deserialized last
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
     pub fn top(&mut self) -> &DeserializerItem {
         let value = self.stack.last();
         value.expect("Can't get top element of an empty stack")
@@ -88,7 +100,7 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut ConfigDeserializer<'de> {
         match value.val {
             Value::Nothing => visitor.visit_bool(false),
             Value::Boolean(b) => visitor.visit_bool(b),
-            _ => Err(ShellError::runtime_error("expected Boolean ")),
+            _ => Err(ShellError::type_mismatch("Boolean")),
         }
     }
 
@@ -123,9 +135,15 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut ConfigDeserializer<'de> {
             Value::Number(i) => i.to_i64(),
             Value::String(s) => s.parse().ok(),
             Value::Boolean(b) => Some(b as i64),
-            Value::List(_) | Value::Nothing | Value::Path(_) | Value::Pattern(_) => None,
+            Value::List(_)
+            | Value::Row(_)
+            | Value::Date(_)
+            | Value::Nothing
+            | Value::Path(_)
+            | Value::Pattern(_)
+            | Value::Block(_) => None,
         }
-        .ok_or_else(|| ShellError::runtime_error("expected Integer"))?;
+        .ok_or_else(|| ShellError::type_mismatch("Integer"))?;
         visitor.visit_i64(val)
     }
 
@@ -174,9 +192,15 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut ConfigDeserializer<'de> {
             Value::Number(i) => i.to_f64(),
             Value::String(s) => s.parse().ok(),
             Value::Boolean(b) => Some(b as i8 as f64),
-            Value::List(_) | Value::Nothing | Value::Path(_) | Value::Pattern(_) => None,
+            Value::List(_)
+            | Value::Row(_)
+            | Value::Date(_)
+            | Value::Nothing
+            | Value::Path(_)
+            | Value::Pattern(_)
+            | Value::Block(_) => None,
         }
-        .ok_or_else(|| ShellError::runtime_error("expected Number"))?;
+        .ok_or_else(|| ShellError::type_mismatch("Number"))?;
         visitor.visit_f64(val)
     }
 
@@ -207,7 +231,8 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut ConfigDeserializer<'de> {
             Value::Pattern(p) => p,
             Value::Path(p) => p.to_string_lossy().to_string(),
             Value::Boolean(b) => b.to_string(),
-            _ => return Err(ShellError::runtime_error("expected String")),
+            Value::Date(d) => d.to_rfc3339(),
+            _ => return Err(ShellError::type_mismatch("String")),
         };
         visitor.visit_string(val)
     }
@@ -276,7 +301,7 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut ConfigDeserializer<'de> {
                 let de = SeqDeserializer::new(&mut self, items.into_iter());
                 visitor.visit_seq(de)
             }
-            _ => Err(ShellError::runtime_error("expected Vec")),
+            _ => Err(ShellError::type_mismatch("Vec")),
         }
     }
 
@@ -290,7 +315,7 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut ConfigDeserializer<'de> {
                 let de = SeqDeserializer::new(&mut self, items.into_iter());
                 visitor.visit_seq(de)
             }
-            _ => Err(ShellError::runtime_error("expected Tuple")),
+            _ => Err(ShellError::type_mismatch("Tuple")),
         }
     }
 
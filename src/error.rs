@@ -8,9 +8,28 @@ use crate::parser::tracable::TracableContext;
 #[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Serialize, Deserialize, Hash)]
 pub struct ShellError {
     pub error: ProximateShellError,
+    /// Boxed so that results carrying an error stay small
+    pub details: Box<Details>,
     pub cause: Option<Box<ShellError>>,
 }
 
+/// What is shown around the message of an error
+#[derive(Debug, Default, Eq, PartialEq, Ord, PartialOrd, Clone, Serialize, Deserialize, Hash)]
+pub struct Details {
+    /// Secondary spans pointing at other parts of the source that are involved
+    pub labels: Vec<Label>,
+    pub help: Option<String>,
+    pub notes: Vec<String>,
+    /// The text the spans point into, when it isn't the line being run, eg the body of a `def`
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Serialize, Deserialize, Hash)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
 impl ShellError {
     pub fn parse_error(
         error: nom::Err<(
@@ -29,7 +48,105 @@ impl ShellError {
         }
     }
     pub fn runtime_error(reason: impl Into<String>) -> ShellError {
-        ProximateShellError::RuntimeError(reason.into()).start()
+        ShellError::new(ErrorKind::Other, reason)
+    }
+
+    /// A runtime error of the given kind, its span is set later with `at`
+    pub fn new(kind: ErrorKind, reason: impl Into<String>) -> ShellError {
+        ProximateShellError::RuntimeError {
+            kind,
+            reason: reason.into(),
+            span: Span::unknown(),
+        }
+        .start()
+    }
+
+    pub fn type_mismatch(expected: impl std::fmt::Display) -> ShellError {
+        ShellError::new(ErrorKind::TypeMismatch, format!("expected {}", expected))
+    }
+
+    /// An error of the filesystem, naming the path it happened on
+    pub fn io(error: std::io::Error, path: &std::path::Path) -> ShellError {
+        ShellError::new(
+            error.kind().into(),
+            format!("{}: {}", path.display(), io_reason(&error)),
+        )
+    }
+
+    /// Points a runtime error at the given span, unless it already points somewhere
+    pub fn at(mut self, at: Span) -> ShellError {
+        if let ProximateShellError::RuntimeError { span, .. } = &mut self.error {
+            if span.is_unknown() {
+                *span = at;
+            }
+        }
+        self
+    }
+
+    /// The span the error is about, if any
+    pub fn span(&self) -> Option<Span> {
+        match &self.error {
+            ProximateShellError::ParseError(span, _)
+            | ProximateShellError::RuntimeError { span, .. }
+                if !span.is_unknown() =>
+            {
+                Some(*span)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the error is a `break` or `continue` on its way to a loop, which `try` and
+    /// `--ignore-errors` let through
+    pub fn is_loop_control(&self) -> bool {
+        matches!(
+            self.error,
+            ProximateShellError::Break | ProximateShellError::Continue
+        )
+    }
+
+    /// The kind of a runtime error, parse errors have none
+    pub fn kind(&self) -> Option<ErrorKind> {
+        match &self.error {
+            ProximateShellError::RuntimeError { kind, .. } => Some(*kind),
+            _ => None,
+        }
+    }
+
+    pub fn with_label(mut self, span: Span, message: impl Into<String>) -> ShellError {
+        self.details.labels.push(Label {
+            span,
+            message: message.into(),
+        });
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> ShellError {
+        self.details.help = Some(help.into());
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> ShellError {
+        self.details.notes.push(note.into());
+        self
+    }
+
+    /// Sets the text the spans point into, unless it was already set by a nested call
+    pub fn with_source(mut self, source: impl Into<String>) -> ShellError {
+        if self.details.source.is_none() {
+            self.details.source = Some(source.into());
+        }
+        self
+    }
+
+    pub fn with_cause(mut self, cause: ShellError) -> ShellError {
+        self.cause = Some(Box::new(cause));
+        self
+    }
+
+    /// Iterates over the error and its causes
+    pub fn chain(&self) -> impl Iterator<Item = &ShellError> {
+        std::iter::successors(Some(self), |err| err.cause.as_deref())
     }
 }
 
@@ -50,7 +167,63 @@ impl serde::de::Error for ShellError {
 
 impl From<std::io::Error> for ShellError {
     fn from(e: std::io::Error) -> Self {
-        Self::runtime_error(e.to_string())
+        ShellError::new(e.kind().into(), io_reason(&e))
+    }
+}
+
+/// The message of an io error without the `(os error 2)` suffix
+fn io_reason(error: &std::io::Error) -> String {
+    let reason = error.to_string();
+    match reason.find(" (os error") {
+        Some(index) => reason[..index].to_string(),
+        None => reason,
+    }
+}
+
+/// What went wrong in a runtime error, so errors can be told apart without looking at the message
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Serialize, Deserialize, Hash)]
+pub enum ErrorKind {
+    NotFound,
+    PermissionDenied,
+    AlreadyExists,
+    /// An argument or an input value has a wrong format
+    InvalidInput,
+    /// A value doesn't have the type needed, eg a string given for an int parameter
+    TypeMismatch,
+    /// An external command couldn't be run or failed
+    ExternalFailed,
+    /// Any other error of the operating system
+    Io,
+    Other,
+}
+
+impl ErrorKind {
+    /// The name shown with the error, eg `not-found`
+    pub fn name(self) -> &'static str {
+        match self {
+            ErrorKind::NotFound => "not-found",
+            ErrorKind::PermissionDenied => "permission-denied",
+            ErrorKind::AlreadyExists => "already-exists",
+            ErrorKind::InvalidInput => "invalid-input",
+            ErrorKind::TypeMismatch => "type-mismatch",
+            ErrorKind::ExternalFailed => "external-failed",
+            ErrorKind::Io => "io",
+            ErrorKind::Other => "other",
+        }
+    }
+}
+
+impl From<std::io::ErrorKind> for ErrorKind {
+    fn from(kind: std::io::ErrorKind) -> Self {
+        match kind {
+            std::io::ErrorKind::NotFound => ErrorKind::NotFound,
+            std::io::ErrorKind::PermissionDenied => ErrorKind::PermissionDenied,
+            std::io::ErrorKind::AlreadyExists => ErrorKind::AlreadyExists,
+            std::io::ErrorKind::InvalidInput | std::io::ErrorKind::InvalidData => {
+                ErrorKind::InvalidInput
+            }
+            _ => ErrorKind::Io,
+        }
     }
 }
 
@@ -59,14 +232,23 @@ impl std::error::Error for ShellError {}
 #[derive(Debug, Eq, PartialEq, Clone, Ord, PartialOrd, Serialize, Deserialize, Hash)]
 pub enum ProximateShellError {
     ParseError(Span, Option<String>),
-    RuntimeError(String),
+    RuntimeError {
+        kind: ErrorKind,
+        reason: String,
+        span: Span,
+    },
+    /// Raised by `break` and caught by the innermost loop
+    Break,
+    /// Raised by `continue` and caught by the innermost loop
+    Continue,
 }
 
 impl ProximateShellError {
     pub fn start(self) -> ShellError {
         ShellError {
-            cause: None,
             error: self,
+            details: Box::new(Details::default()),
+            cause: None,
         }
     }
 }
@@ -77,18 +259,12 @@ impl std::fmt::Display for ProximateShellError {
             f,
             "{}",
             match self {
-                ProximateShellError::ParseError(span, reason) => {
-                    let reason = reason.clone().unwrap_or_default();
-                    format!(
-                        "{}{}{}",
-                        " ".repeat(span.start()),
-                        "^".repeat(span.len()),
-                        reason
-                    )
-                }
-                ProximateShellError::RuntimeError(reason) => {
-                    reason.clone()
+                ProximateShellError::ParseError(_, reason) => {
+                    reason.clone().unwrap_or_else(|| "parse error".to_string())
                 }
+                ProximateShellError::RuntimeError { reason, .. } => reason.clone(),
+                ProximateShellError::Break => "break outside of a loop".to_string(),
+                ProximateShellError::Continue => "continue outside of a loop".to_string(),
             }
         )
     }
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+use crate::parser::token::SpannedToken;
+
+/// A pipeline in braces that is run later by the command receiving it, eg the body of `each`.
+/// The spans of the pipeline point into `source`, so the line it was parsed from is kept along.
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct Block {
+    pub source: String,
+    /// The names the arguments are bound to, eg `err` in `{ |err| echo $err.message }`
+    pub params: Vec<String>,
+    pub pipeline: SpannedToken,
+}
+
+impl Block {
+    /// The name of the nth parameter, or the given default if the block doesn't declare it
+    pub fn param<'a>(&'a self, pos: usize, default: &'a str) -> &'a str {
+        self.params.get(pos).map_or(default, String::as_str)
+    }
+
+    /// The text of the block including the braces
+    pub fn text(&self) -> &str {
+        self.pipeline.span.slice(&self.source)
+    }
+}
@@ -3,13 +3,59 @@ use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
 use crate::evaluate::value::Value;
+use crate::parser::span::Span;
 
 /// Associated information for the call of a command, including the args passed to the command and a tag that spans the name of the command being called
 #[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
 pub struct CallInfo {
     /// The arguments associated with this call
     pub args: EvaluatedArgs,
-    //    pub name: Span,
+    /// The span of the command name
+    pub name_span: Span,
+    /// The span of the whole call, including the arguments
+    pub span: Span,
+    /// The line the call was parsed from, which the spans point into
+    pub source: String,
+    /// The spans of the positional arguments
+    pub positional_spans: Vec<Span>,
+    /// The spans of the named arguments, the value or the flag of a switch
+    pub named_spans: IndexMap<String, Span>,
+}
+
+impl CallInfo {
+    /// The span of the nth positional argument, or of the command name if there is none
+    pub fn nth_span(&self, pos: usize) -> Span {
+        self.positional_spans
+            .get(pos)
+            .copied()
+            .unwrap_or(self.name_span)
+    }
+
+    /// The span of the named argument, or of the command name if it wasn't given
+    pub fn named_span(&self, name: &str) -> Span {
+        self.named_spans
+            .get(name)
+            .copied()
+            .unwrap_or(self.name_span)
+    }
+
+    /// The span of the positional arguments from the given position to the last
+    pub fn span_from(&self, from: usize) -> Span {
+        match (
+            self.positional_spans.get(from),
+            self.positional_spans.last(),
+        ) {
+            (Some(first), Some(last)) => first.until(*last),
+            _ => self.name_span,
+        }
+    }
+
+    /// The unparsed text after the command name, for commands that take their arguments verbatim
+    pub fn raw_args(&self) -> &str {
+        Span::new(self.name_span.end(), self.span.end())
+            .slice(&self.source)
+            .trim()
+    }
 }
 
 /// The set of positional and named arguments, after their values have been evaluated.
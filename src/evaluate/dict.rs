@@ -0,0 +1,74 @@
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::evaluate::value::Value;
+
+/// An ordered set of columns, the payload of a `Value::Row`
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Dictionary {
+    pub entries: IndexMap<String, Value>,
+}
+
+impl Dictionary {
+    pub fn new(entries: IndexMap<String, Value>) -> Dictionary {
+        Dictionary { entries }
+    }
+
+    /// Gets the value of the given column, if present
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.entries.get(key)
+    }
+
+    /// Inserts a column, replacing the previous value if any
+    pub fn insert(&mut self, key: impl Into<String>, value: Value) {
+        self.entries.insert(key.into(), value);
+    }
+
+    /// Iterates over the column names in order
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.entries.keys()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Value)> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl PartialOrd for Dictionary {
+    fn partial_cmp(&self, other: &Dictionary) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Dictionary {
+    fn cmp(&self, other: &Dictionary) -> Ordering {
+        self.entries.iter().cmp(other.entries.iter())
+    }
+}
+
+impl Hash for Dictionary {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.entries.len().hash(state);
+        for (key, value) in self.entries.iter() {
+            key.hash(state);
+            value.hash(state);
+        }
+    }
+}
+
+impl From<IndexMap<String, Value>> for Dictionary {
+    fn from(entries: IndexMap<String, Value>) -> Dictionary {
+        Dictionary { entries }
+    }
+}
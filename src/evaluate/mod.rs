@@ -5,37 +5,115 @@ pub(crate) use value::Value;
 
 use crate::commands::BoxedCommand;
 use crate::context::CommandRegistry;
-use crate::error::ShellError;
+use crate::error::{ErrorKind, ShellError};
+use crate::evaluate::block::Block;
 use crate::evaluate::call_info::EvaluatedArgs;
+use crate::evaluate::scope::Scope;
+use crate::expand;
 use crate::parser::hir;
+use crate::parser::span::SpannedItem;
+use crate::parser::syntax_shape::SyntaxShape;
 use crate::parser::token::{SpannedToken, Token};
+use crate::signature::NamedType;
 
+pub mod block;
 pub mod call_info;
+pub mod dict;
+pub mod operator;
+pub mod scope;
 pub mod value;
 
-fn evaluate_expr(spanned: &SpannedToken, source: &str) -> Result<Value, ShellError> {
+pub(crate) fn evaluate_expr(
+    spanned: &SpannedToken,
+    source: &str,
+    scope: &Scope,
+) -> Result<Value, ShellError> {
     let token = &spanned.item;
     match token {
+        Token::Variable(path) => scope.lookup(path.slice(source)).cloned().ok_or_else(|| {
+            ShellError::new(
+                ErrorKind::NotFound,
+                format!("Unknown variable: {}", spanned.span.slice(source)),
+            )
+            .at(spanned.span)
+        }),
+        Token::List(items) => items
+            .iter()
+            .map(|item| evaluate_expr(item, source, scope))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::List),
+        Token::Block(params, pipeline) => Ok(Value::Block(Block {
+            source: source.to_string(),
+            params: params.iter().map(|param| param.string(source)).collect(),
+            pipeline: Token::Pipeline(pipeline.clone()).spanned(spanned.span),
+        })),
         Token::String(s) => Ok(Value::String(s.string(source))),
         Token::Bare | Token::GlobPattern | Token::ExternalWord => {
             Ok(Value::String(spanned.span.string(source)))
         }
-        Token::Flag(_) | Token::Whitespace | Token::Separator | Token::Pipeline(_) => Err(
-            ShellError::runtime_error(format!("unexpected {}", token.desc())),
-        ),
+        Token::Flag(_) | Token::Whitespace | Token::Separator | Token::Pipeline(_) => {
+            Err(ShellError::runtime_error(format!("unexpected {}", token.desc())).at(spanned.span))
+        }
+    }
+}
+
+/// Evaluates an argument given for a parameter of the shape, in the words given for paths and
+/// patterns `~`, `~user` and `$VAR` are expanded
+fn evaluate_arg(
+    spanned: &SpannedToken,
+    shape: Option<SyntaxShape>,
+    source: &str,
+    scope: &Scope,
+) -> Result<Value, ShellError> {
+    let is_path = matches!(
+        shape,
+        Some(SyntaxShape::Path) | Some(SyntaxShape::Pattern)
+    );
+    match &spanned.item {
+        Token::Bare | Token::GlobPattern | Token::ExternalWord if is_path => {
+            let word = spanned.span.string(source);
+            expand::expand_word(&word, |name| {
+                scope
+                    .lookup(name)
+                    .map(Value::to_string)
+                    .or_else(|| expand::env_var(name))
+            })
+            .map(Value::String)
+            .map_err(|err| err.at(spanned.span))
+        }
+        Token::Variable(path) if is_path && scope.lookup(path.slice(source)).is_none() => {
+            match expand::env_var(path.slice(source)) {
+                Some(value) => Ok(Value::String(value)),
+                None => evaluate_expr(spanned, source, scope),
+            }
+        }
+        _ => evaluate_expr(spanned, source, scope),
     }
 }
 
 pub(crate) fn evaluate_args(
     call: hir::Call,
-    _command: BoxedCommand,
+    command: BoxedCommand,
     _registry: &CommandRegistry,
+    scope: &Scope,
     source: &str,
 ) -> Result<EvaluatedArgs, ShellError> {
+    let signature = command.signature();
     let positional: Result<Option<Vec<_>>, _> = call
         .positional
         .as_ref()
-        .map(|p| p.iter().map(|s| evaluate_expr(s, source)).collect())
+        .map(|p| {
+            p.iter()
+                .enumerate()
+                .map(|(i, s)| {
+                    let shape = match signature.positional.get(i) {
+                        Some((positional, _)) => Some(positional.syntax_type()),
+                        None => signature.rest_positional.as_ref().map(|(shape, _)| *shape),
+                    };
+                    evaluate_arg(s, shape, source, scope)
+                })
+                .collect()
+        })
         .transpose();
     let positional = positional?;
     let named: Result<Option<IndexMap<String, Value>>, ShellError> = call
@@ -49,7 +127,12 @@ pub(crate) fn evaluate_args(
                         results.insert(name.clone(), Value::Boolean(true));
                     }
                     hir::NamedValue::Value(ref expr) => {
-                        results.insert(name.clone(), evaluate_expr(expr, source)?);
+                        let shape = match signature.named.get(name) {
+                            Some((NamedType::Mandatory(shape), _))
+                            | Some((NamedType::Optional(shape), _)) => Some(*shape),
+                            _ => None,
+                        };
+                        results.insert(name.clone(), evaluate_arg(expr, shape, source, scope)?);
                     }
                     _ => {}
                 };
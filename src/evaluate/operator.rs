@@ -0,0 +1,139 @@
+use std::cmp::Ordering;
+
+use bigdecimal::BigDecimal;
+use num_bigint::BigInt;
+
+use crate::error::{ErrorKind, ShellError};
+use crate::evaluate::Value;
+
+/// The comparison operators understood by `where` and the conditions of control flow commands
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Operator {
+    Equal,
+    NotEqual,
+    LessThan,
+    GreaterThan,
+    LessThanOrEqual,
+    GreaterThanOrEqual,
+    Contains,
+    NotContains,
+}
+
+impl Operator {
+    pub fn parse(op: &str) -> Option<Operator> {
+        match op {
+            "==" => Some(Operator::Equal),
+            "!=" => Some(Operator::NotEqual),
+            "<" => Some(Operator::LessThan),
+            ">" => Some(Operator::GreaterThan),
+            "<=" => Some(Operator::LessThanOrEqual),
+            ">=" => Some(Operator::GreaterThanOrEqual),
+            "=~" => Some(Operator::Contains),
+            "!~" => Some(Operator::NotContains),
+            _ => None,
+        }
+    }
+
+    /// Applies the operator, coercing the operands where that makes sense (eg an integer column
+    /// compared with `10kb`)
+    pub fn apply(self, left: &Value, right: &Value) -> Result<bool, ShellError> {
+        match self {
+            Operator::Contains | Operator::NotContains => {
+                let contains = left.to_string().contains(&right.to_string());
+                Ok(contains == (self == Operator::Contains))
+            }
+            _ => {
+                let ordering = compare_values(left, right).ok_or_else(|| {
+                    ShellError::new(
+                        ErrorKind::TypeMismatch,
+                        format!(
+                            "Can't compare {} with {}",
+                            left.type_name(),
+                            right.type_name()
+                        ),
+                    )
+                })?;
+                Ok(match self {
+                    Operator::Equal => ordering == Ordering::Equal,
+                    Operator::NotEqual => ordering != Ordering::Equal,
+                    Operator::LessThan => ordering == Ordering::Less,
+                    Operator::GreaterThan => ordering == Ordering::Greater,
+                    Operator::LessThanOrEqual => ordering != Ordering::Greater,
+                    Operator::GreaterThanOrEqual => ordering != Ordering::Less,
+                    Operator::Contains | Operator::NotContains => unreachable!(),
+                })
+            }
+        }
+    }
+}
+
+/// Compares two values of compatible types. Returns `None` when the values can't be compared, eg
+/// a number with a word.
+pub fn compare_values(left: &Value, right: &Value) -> Option<Ordering> {
+    match (left, right) {
+        (Value::Int(a), Value::Int(b)) => Some(a.cmp(b)),
+        (Value::Int(a), Value::Number(b)) => Some(BigDecimal::new(a.clone(), 0).cmp(b)),
+        (Value::Number(a), Value::Int(b)) => Some(a.cmp(&BigDecimal::new(b.clone(), 0))),
+        (Value::Number(a), Value::Number(b)) => Some(a.cmp(b)),
+        (Value::String(s), Value::Int(_)) | (Value::String(s), Value::Number(_)) => {
+            match parse_literal(s) {
+                literal @ Value::Int(_) | literal @ Value::Number(_) => {
+                    compare_values(&literal, right)
+                }
+                _ => None,
+            }
+        }
+        (Value::Int(_), Value::String(_)) | (Value::Number(_), Value::String(_)) => {
+            compare_values(right, left).map(Ordering::reverse)
+        }
+        (Value::Boolean(a), Value::Boolean(b)) => Some(a.cmp(b)),
+        (Value::Date(a), Value::Date(b)) => Some(a.cmp(b)),
+        (Value::Nothing, Value::Nothing) => Some(Ordering::Equal),
+        (Value::String(_), _) | (Value::Pattern(_), _) | (Value::Path(_), _) if is_text(right) => {
+            Some(left.to_string().cmp(&right.to_string()))
+        }
+        (Value::List(a), Value::List(b)) => Some(a.cmp(b)),
+        (Value::Row(a), Value::Row(b)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+fn is_text(value: &Value) -> bool {
+    matches!(value, Value::String(_) | Value::Pattern(_) | Value::Path(_))
+}
+
+/// Interprets a word typed on the command line: integers, decimals, sizes with a unit like `10kb`
+/// and booleans are recognized, anything else stays a string.
+pub fn parse_literal(s: &str) -> Value {
+    if let Ok(i) = s.parse::<BigInt>() {
+        return Value::Int(i);
+    }
+    if let Ok(d) = s.parse::<BigDecimal>() {
+        return Value::Number(d);
+    }
+    match s {
+        "true" => return Value::Boolean(true),
+        "false" => return Value::Boolean(false),
+        _ => {}
+    }
+    let lower = s.to_lowercase();
+    let units = [
+        ("kb", 1u64 << 10),
+        ("mb", 1 << 20),
+        ("gb", 1 << 30),
+        ("tb", 1 << 40),
+        ("b", 1),
+    ];
+    for (unit, multiplier) in units.iter() {
+        if let Some(amount) = lower.strip_suffix(unit) {
+            if let Ok(amount) = amount.parse::<BigDecimal>() {
+                let bytes = amount * BigDecimal::from(*multiplier);
+                let (digits, scale) = bytes.with_scale(0).as_bigint_and_exponent();
+                if scale == 0 {
+                    return Value::Int(digits);
+                }
+            }
+        }
+    }
+    Value::String(s.to_string())
+}
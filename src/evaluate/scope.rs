@@ -0,0 +1,35 @@
+use indexmap::IndexMap;
+
+use crate::evaluate::value::Value;
+
+/// The variables visible to a pipeline, eg `$it` while a block runs inside `each`
+#[derive(Debug, Default, Clone)]
+pub struct Scope {
+    vars: IndexMap<String, Value>,
+}
+
+impl Scope {
+    pub fn new() -> Scope {
+        Scope::default()
+    }
+
+    /// Binds a variable, shadowing the previous value if any
+    pub fn insert(&mut self, name: impl Into<String>, value: Value) {
+        self.vars.insert(name.into(), value);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.vars.get(name)
+    }
+
+    /// Looks up a variable reference like `it.name`: the first member names the variable and the
+    /// rest is a column path into its value
+    pub fn lookup(&self, path: &str) -> Option<&Value> {
+        let mut members = path.splitn(2, '.');
+        let value = self.get(members.next()?)?;
+        match members.next() {
+            Some(rest) => value.get_data_by_column_path(rest),
+            None => Some(value),
+        }
+    }
+}
@@ -1,10 +1,14 @@
 use std::path::PathBuf;
 use std::string::ToString;
 
-use bigdecimal::BigDecimal;
+use bigdecimal::{BigDecimal, Zero};
+use chrono::{DateTime, Utc};
 use num_bigint::BigInt;
 use serde::{Deserialize, Serialize};
 
+use crate::evaluate::block::Block;
+use crate::evaluate::dict::Dictionary;
+
 #[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Deserialize, Serialize)]
 pub enum Value {
     Nothing,
@@ -19,7 +23,60 @@ pub enum Value {
     /// A file path
     Path(PathBuf),
     Boolean(bool),
+    /// A point in time, eg the modification time of a file
+    Date(DateTime<Utc>),
     List(Vec<Value>),
+    /// A set of named columns, eg a parsed JSON object or a CSV record
+    Row(Dictionary),
+    /// A block of code, eg the `{ echo $it }` passed to `each`
+    Block(Block),
+}
+
+impl Value {
+    /// A short description of the kind of value, used in error messages
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Nothing => "nothing",
+            Value::Int(_) => "integer",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Pattern(_) => "pattern",
+            Value::Path(_) => "path",
+            Value::Boolean(_) => "boolean",
+            Value::Date(_) => "date",
+            Value::List(_) => "list",
+            Value::Row(_) => "row",
+            Value::Block(_) => "block",
+        }
+    }
+
+    /// Follows a dotted path like `c.d` or `items.0` through nested rows and lists
+    pub fn get_data_by_column_path(&self, path: &str) -> Option<&Value> {
+        let mut current = self;
+        for member in path.split('.') {
+            current = match current {
+                Value::Row(d) => d.get(member)?,
+                Value::List(l) => l.get(member.parse::<usize>().ok()?)?,
+                _ => return None,
+            };
+        }
+        Some(current)
+    }
+
+    /// The value as a condition: false, zero, nothing and empty values are falsy, the rest is
+    /// truthy
+    pub fn is_true(&self) -> bool {
+        match self {
+            Value::Nothing => false,
+            Value::Boolean(b) => *b,
+            Value::Int(i) => !i.is_zero(),
+            Value::Number(n) => !n.is_zero(),
+            Value::String(s) => !s.is_empty(),
+            Value::List(l) => !l.is_empty(),
+            Value::Row(d) => !d.is_empty(),
+            _ => true,
+        }
+    }
 }
 
 impl ToString for Value {
@@ -31,8 +88,46 @@ impl ToString for Value {
             Value::Pattern(s) => s.clone(),
             Value::Path(s) => s.to_string_lossy().to_string(),
             Value::Boolean(b) => b.to_string(),
+            Value::Date(d) => format_date(d),
             Value::List(v) => v.iter().map(Self::to_string).collect::<Vec<_>>().join(" "),
+            Value::Row(d) => format!(
+                "{{{}}}",
+                d.iter()
+                    .map(|(k, v)| format!("{}: {}", k, v.to_string()))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Value::Block(b) => b.text().to_string(),
             Value::Nothing => String::new(),
         }
     }
 }
+
+/// Formats a date relative to now, eg `3 days ago`, falling back to the date itself for anything
+/// older than a year
+fn format_date(date: &DateTime<Utc>) -> String {
+    let duration = Utc::now().signed_duration_since(*date);
+    let (amount, unit) = if duration.num_seconds() < 0 {
+        return date.format("%Y-%m-%d %H:%M:%S").to_string();
+    } else if duration.num_minutes() < 1 {
+        return "just now".to_string();
+    } else if duration.num_hours() < 1 {
+        (duration.num_minutes(), "minute")
+    } else if duration.num_days() < 1 {
+        (duration.num_hours(), "hour")
+    } else if duration.num_weeks() < 1 {
+        (duration.num_days(), "day")
+    } else if duration.num_days() < 30 {
+        (duration.num_weeks(), "week")
+    } else if duration.num_days() < 365 {
+        (duration.num_days() / 30, "month")
+    } else {
+        return date.format("%Y-%m-%d").to_string();
+    };
+    format!(
+        "{} {}{} ago",
+        amount,
+        unit,
+        if amount == 1 { "" } else { "s" }
+    )
+}
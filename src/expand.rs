@@ -0,0 +1,321 @@
+//! The expansion of words done by the shell. Paths given to internal commands get `~`, `~user`
+//! and `$VAR` expanded. The arguments of external commands, which are run directly and not
+//! through the system shell, get alternatives in braces like `{a,b}` expanded too, and then the
+//! globs `*`, `?` and `**`.
+
+use std::path::{Path, PathBuf};
+
+use glob::MatchOptions;
+
+use crate::error::{ErrorKind, ShellError};
+
+/// Expands `~`, `~user`, `$VAR` and `${VAR}` in a word, looking the variables up with `lookup`.
+/// An unknown variable is an error.
+pub fn expand_word(
+    word: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<String, ShellError> {
+    let expanded = shellexpand::full_with_context(word, dirs::home_dir, |name| {
+        lookup(name).map(Some).ok_or(())
+    })
+    .map_err(|e| {
+        ShellError::new(
+            ErrorKind::NotFound,
+            format!("Unknown variable: ${}", e.var_name),
+        )
+    })?;
+    if !word.starts_with('~') {
+        return Ok(expanded.to_string());
+    }
+    // `~` alone was expanded above, `~user` is left
+    let (name, rest) = match expanded.find('/') {
+        Some(index) => expanded.split_at(index),
+        None => (expanded.as_ref(), ""),
+    };
+    match name.strip_prefix('~').and_then(user_home) {
+        Some(home) => Ok(format!("{}{}", home.display(), rest)),
+        None => Ok(expanded.to_string()),
+    }
+}
+
+/// The value of an environment variable, for `expand_word`
+pub fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok()
+}
+
+#[cfg(unix)]
+fn user_home(name: &str) -> Option<PathBuf> {
+    use std::ffi::{CStr, CString};
+
+    if name.is_empty() {
+        return None;
+    }
+    let name = CString::new(name).ok()?;
+    // the entry is in static storage overwritten by the next call, it is copied right away
+    unsafe {
+        let passwd = libc::getpwnam(name.as_ptr());
+        if passwd.is_null() {
+            return None;
+        }
+        let dir = CStr::from_ptr((*passwd).pw_dir);
+        Some(PathBuf::from(dir.to_string_lossy().to_string()))
+    }
+}
+
+#[cfg(not(unix))]
+fn user_home(_name: &str) -> Option<PathBuf> {
+    None
+}
+
+/// Expands the alternatives in braces, `a{b,c{d,e}}` becomes `ab acd ace`. Braces without a comma
+/// directly inside are kept, like in `find -exec rm {} ;`.
+pub fn expand_braces(word: &str) -> Vec<String> {
+    match find_braces(word) {
+        Some((start, end, alternatives)) => alternatives
+            .iter()
+            .flat_map(|alternative| {
+                expand_braces(&format!(
+                    "{}{}{}",
+                    &word[..start],
+                    alternative,
+                    &word[end + 1..]
+                ))
+            })
+            .collect(),
+        None => vec![word.to_string()],
+    }
+}
+
+/// The first pair of braces with alternatives: the positions of the braces and the alternatives
+fn find_braces(word: &str) -> Option<(usize, usize, Vec<&str>)> {
+    for (start, _) in word.match_indices('{') {
+        let mut depth = 0;
+        let mut from = start + 1;
+        let mut alternatives = vec![];
+        for (index, c) in word[start..].char_indices() {
+            let index = start + index;
+            match c {
+                '{' => depth += 1,
+                ',' if depth == 1 => {
+                    alternatives.push(&word[from..index]);
+                    from = index + 1;
+                }
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        if alternatives.is_empty() {
+                            break;
+                        }
+                        alternatives.push(&word[from..index]);
+                        return Some((start, index, alternatives));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    None
+}
+
+/// Expands a glob into the paths it matches in `cwd`, sorted. A word without `*` or `?` is kept.
+/// When nothing matches the word is an error, or is kept if `literal` is set.
+pub fn expand_glob(word: &str, cwd: &Path, literal: bool) -> Result<Vec<String>, ShellError> {
+    if !word.contains(['*', '?']) {
+        return Ok(vec![word.to_string()]);
+    }
+    let relative = !Path::new(word).is_absolute();
+    let pattern = if relative {
+        format!("{}/{}", glob::Pattern::escape(&cwd.to_string_lossy()), word)
+    } else {
+        word.to_string()
+    };
+    // like in other shells `*` doesn't match hidden files
+    let options = MatchOptions {
+        require_literal_leading_dot: true,
+        ..MatchOptions::default()
+    };
+    let paths = glob::glob_with(&pattern, options).map_err(|e| {
+        ShellError::new(
+            ErrorKind::InvalidInput,
+            format!("Invalid pattern {}: {}", word, e.msg),
+        )
+    })?;
+    let mut matches = paths
+        .filter_map(Result::ok)
+        .map(|path| match path.strip_prefix(cwd) {
+            Ok(name) if relative => name.to_string_lossy().to_string(),
+            _ => path.to_string_lossy().to_string(),
+        })
+        .collect::<Vec<_>>();
+    if matches.is_empty() {
+        if literal {
+            return Ok(vec![word.to_string()]);
+        }
+        return Err(
+            ShellError::new(ErrorKind::NotFound, format!("No files match {}", word)).with_help(
+                "quote the argument to pass it as it is, or `config set glob_no_match literal`",
+            ),
+        );
+    }
+    matches.sort();
+    Ok(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{expand_braces, expand_glob, expand_word};
+    use crate::error::ErrorKind;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "NAME" => Some("shell".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn braces_expand_alternatives_in_order() {
+        assert_eq!(expand_braces("a{b,c}d"), vec!["abd", "acd"]);
+        assert_eq!(
+            expand_braces("src/{main,lib}.rs"),
+            vec!["src/main.rs", "src/lib.rs"]
+        );
+        assert_eq!(expand_braces("{a,b}{1,2}"), vec!["a1", "a2", "b1", "b2"]);
+        assert_eq!(expand_braces("x{,y}"), vec!["x", "xy"]);
+    }
+
+    #[test]
+    fn nested_braces_expand_inside_out() {
+        assert_eq!(expand_braces("a{b,c{d,e}}"), vec!["ab", "acd", "ace"]);
+        assert_eq!(expand_braces("{a,{b,c}}z"), vec!["az", "bz", "cz"]);
+    }
+
+    #[test]
+    fn braces_without_alternatives_are_kept() {
+        assert_eq!(expand_braces("{}"), vec!["{}"]);
+        assert_eq!(expand_braces("{a}"), vec!["{a}"]);
+        assert_eq!(expand_braces("plain"), vec!["plain"]);
+        // the empty braces are kept while the alternatives after them are expanded
+        assert_eq!(expand_braces("{}{a,b}"), vec!["{}a", "{}b"]);
+    }
+
+    #[test]
+    fn unbalanced_braces_are_kept() {
+        assert_eq!(expand_braces("{a,b"), vec!["{a,b"]);
+        assert_eq!(expand_braces("a,b}"), vec!["a,b}"]);
+        assert_eq!(expand_braces("{{a,b}"), vec!["{a", "{b"]);
+    }
+
+    /// A directory with `a.rs`, `b.rs`, `.hidden.rs`, `notes.txt` and `sub/c.rs`
+    fn glob_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("shell-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        for file in &["a.rs", "b.rs", ".hidden.rs", "notes.txt", "sub/c.rs"] {
+            std::fs::write(dir.join(file), "").unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn globs_match_relative_to_the_directory() {
+        let dir = glob_dir("glob-relative");
+        assert_eq!(
+            expand_glob("*.rs", &dir, false).unwrap(),
+            vec!["a.rs", "b.rs"]
+        );
+        assert_eq!(
+            expand_glob("?.rs", &dir, false).unwrap(),
+            vec!["a.rs", "b.rs"]
+        );
+        assert_eq!(
+            expand_glob("**/*.rs", &dir, false).unwrap(),
+            vec!["a.rs", "b.rs", "sub/c.rs"]
+        );
+        assert_eq!(expand_glob("sub/*", &dir, false).unwrap(), vec!["sub/c.rs"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn globs_with_an_absolute_path_give_absolute_paths() {
+        let dir = glob_dir("glob-absolute");
+        let pattern = format!("{}/*.txt", dir.display());
+        let elsewhere = std::env::temp_dir();
+        assert_eq!(
+            expand_glob(&pattern, &elsewhere, false).unwrap(),
+            vec![dir.join("notes.txt").display().to_string()]
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn globs_only_match_hidden_files_with_a_leading_dot() {
+        let dir = glob_dir("glob-hidden");
+        assert!(!expand_glob("*", &dir, false)
+            .unwrap()
+            .contains(&".hidden.rs".to_string()));
+        assert_eq!(
+            expand_glob(".*.rs", &dir, false).unwrap(),
+            vec![".hidden.rs"]
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn globs_matching_nothing_fail_or_are_kept() {
+        let dir = glob_dir("glob-nothing");
+        let err = expand_glob("*.md", &dir, false).unwrap_err();
+        assert_eq!(err.kind(), Some(ErrorKind::NotFound));
+        assert_eq!(expand_glob("*.md", &dir, true).unwrap(), vec!["*.md"]);
+        // a word without wildcards is kept even if there is no such file
+        assert_eq!(
+            expand_glob("missing.md", &dir, false).unwrap(),
+            vec!["missing.md"]
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn words_expand_variables() {
+        assert_eq!(expand_word("$NAME/src", lookup).unwrap(), "shell/src");
+        assert_eq!(expand_word("v${NAME}2", lookup).unwrap(), "vshell2");
+        assert_eq!(expand_word("a${EMPTY}b", lookup).unwrap(), "ab");
+        assert_eq!(expand_word("plain", lookup).unwrap(), "plain");
+        let err = expand_word("$MISSING", lookup).unwrap_err();
+        assert_eq!(err.kind(), Some(ErrorKind::NotFound));
+    }
+
+    #[test]
+    fn words_expand_the_home_directory() {
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(
+            expand_word("~", lookup).unwrap(),
+            home.display().to_string()
+        );
+        assert_eq!(
+            expand_word("~/src", lookup).unwrap(),
+            home.join("src").display().to_string()
+        );
+        // only at the start of the word
+        assert_eq!(expand_word("a~b", lookup).unwrap(), "a~b");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn words_expand_the_home_directory_of_a_user() {
+        assert_eq!(
+            expand_word("~root", lookup).unwrap(),
+            super::user_home("root").unwrap().display().to_string()
+        );
+        assert_eq!(
+            expand_word("~root/.profile", lookup).unwrap(),
+            format!("{}/.profile", super::user_home("root").unwrap().display())
+        );
+        assert_eq!(
+            expand_word("~no-such-user/x", lookup).unwrap(),
+            "~no-such-user/x"
+        );
+    }
+}
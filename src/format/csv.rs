@@ -0,0 +1,92 @@
+use indexmap::IndexMap;
+
+use crate::error::ShellError;
+use crate::evaluate::dict::Dictionary;
+use crate::evaluate::Value;
+
+/// Parses delimited text into a list of rows, using the first record as the column names
+pub fn from_csv_string_to_value(
+    s: &str,
+    separator: u8,
+    headerless: bool,
+) -> Result<Value, ShellError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(!headerless)
+        .delimiter(separator)
+        .flexible(true)
+        .from_reader(s.as_bytes());
+    let headers = if headerless {
+        vec![]
+    } else {
+        reader
+            .headers()
+            .map_err(csv_error)?
+            .iter()
+            .map(String::from)
+            .collect::<Vec<_>>()
+    };
+    let mut rows = vec![];
+    for record in reader.records() {
+        let record = record.map_err(csv_error)?;
+        let mut entries = IndexMap::new();
+        for (i, field) in record.iter().enumerate() {
+            let name = match headers.get(i) {
+                Some(name) => name.clone(),
+                None => format!("Column{}", i + 1),
+            };
+            entries.insert(name, Value::String(field.to_string()));
+        }
+        rows.push(Value::Row(Dictionary::new(entries)));
+    }
+    Ok(Value::List(rows))
+}
+
+/// Writes rows as delimited text. The header is the union of all the columns, in the order
+/// they are first seen.
+pub fn values_to_csv_string(values: &[Value], separator: u8) -> Result<String, ShellError> {
+    let mut headers: Vec<String> = vec![];
+    for value in values {
+        match value {
+            Value::Row(d) => {
+                for key in d.keys() {
+                    if !headers.contains(key) {
+                        headers.push(key.clone());
+                    }
+                }
+            }
+            _ => {
+                return Err(ShellError::runtime_error(
+                    "Could not convert to CSV: expected rows",
+                ));
+            }
+        }
+    }
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(separator)
+        .from_writer(vec![]);
+    writer.write_record(&headers).map_err(csv_error)?;
+    for value in values {
+        if let Value::Row(d) = value {
+            let record = headers
+                .iter()
+                .map(|h| d.get(h).map(cell).unwrap_or_default());
+            writer.write_record(record).map_err(csv_error)?;
+        }
+    }
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| ShellError::runtime_error(e.to_string()))?;
+    String::from_utf8(bytes).map_err(|e| ShellError::runtime_error(e.to_string()))
+}
+
+/// Dates are written in full, since their display form is relative to now
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Date(d) => d.to_rfc3339(),
+        value => value.to_string(),
+    }
+}
+
+fn csv_error(e: csv::Error) -> ShellError {
+    ShellError::runtime_error(format!("Could not parse as CSV: {}", e))
+}
@@ -0,0 +1,100 @@
+use indexmap::IndexMap;
+
+use crate::error::ShellError;
+use crate::evaluate::dict::Dictionary;
+use crate::evaluate::Value;
+
+/// Parses an INI document into a row of sections. Keys that appear before the first section
+/// header are kept at the top level.
+pub fn from_ini_string_to_value(s: &str) -> Result<Value, ShellError> {
+    let mut root = IndexMap::new();
+    let mut section: Option<(String, IndexMap<String, Value>)> = None;
+    for (i, line) in s.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('[') && line.ends_with(']') {
+            if let Some((name, entries)) = section.take() {
+                root.insert(name, Value::Row(Dictionary::new(entries)));
+            }
+            let name = line[1..line.len() - 1].trim().to_string();
+            section = Some((name, IndexMap::new()));
+            continue;
+        }
+        let (key, value) = match line.find('=') {
+            Some(pos) => (line[..pos].trim(), line[pos + 1..].trim()),
+            None => {
+                return Err(ShellError::runtime_error(format!(
+                    "Could not parse as INI: expected key = value at line {}",
+                    i + 1
+                )));
+            }
+        };
+        let value = Value::String(unquote(value).to_string());
+        match section.as_mut() {
+            Some((_, entries)) => entries.insert(key.to_string(), value),
+            None => root.insert(key.to_string(), value),
+        };
+    }
+    if let Some((name, entries)) = section.take() {
+        root.insert(name, Value::Row(Dictionary::new(entries)));
+    }
+    Ok(Value::Row(Dictionary::new(root)))
+}
+
+fn unquote(s: &str) -> &str {
+    if s.len() >= 2
+        && ((s.starts_with('"') && s.ends_with('"')) || (s.starts_with('\'') && s.ends_with('\'')))
+    {
+        &s[1..s.len() - 1]
+    } else {
+        s
+    }
+}
+
+pub fn value_to_ini_string(v: &Value) -> Result<String, ShellError> {
+    let dict = match v {
+        Value::Row(d) => d,
+        _ => {
+            return Err(ShellError::runtime_error(
+                "Could not convert to INI: expected a row at the top level",
+            ));
+        }
+    };
+    let mut out = String::new();
+    for (key, value) in dict.iter() {
+        if let Value::Row(_) = value {
+            continue;
+        }
+        out.push_str(&format!("{} = {}\n", key, plain(value)));
+    }
+    for (name, value) in dict.iter() {
+        if let Value::Row(section) = value {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            out.push_str(&format!("[{}]\n", name));
+            for (key, value) in section.iter() {
+                match value {
+                    Value::Row(_) | Value::List(_) => {
+                        return Err(ShellError::runtime_error(format!(
+                            "Could not convert to INI: {}.{} is not a plain value",
+                            name, key
+                        )));
+                    }
+                    _ => out.push_str(&format!("{} = {}\n", key, plain(value))),
+                }
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Dates are written in RFC 3339 so that they can be read back
+fn plain(value: &Value) -> String {
+    match value {
+        Value::Date(d) => d.to_rfc3339(),
+        value => value.to_string(),
+    }
+}
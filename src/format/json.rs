@@ -0,0 +1,82 @@
+use bigdecimal::{FromPrimitive, ToPrimitive};
+use indexmap::IndexMap;
+use num_bigint::BigInt;
+
+use crate::error::ShellError;
+use crate::evaluate::dict::Dictionary;
+use crate::evaluate::Value;
+
+pub fn from_json_string_to_value(s: &str) -> Result<Value, ShellError> {
+    let json: serde_json::Value = serde_json::from_str(s)
+        .map_err(|e| ShellError::runtime_error(format!("Could not parse as JSON: {}", e)))?;
+    Ok(convert_json_value_to_value(json))
+}
+
+fn convert_json_value_to_value(v: serde_json::Value) -> Value {
+    match v {
+        serde_json::Value::Null => Value::Nothing,
+        serde_json::Value::Bool(b) => Value::Boolean(b),
+        serde_json::Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Value::Int(BigInt::from(i))
+            } else if let Some(d) = n.as_f64().and_then(bigdecimal::BigDecimal::from_f64) {
+                Value::Number(d)
+            } else {
+                Value::String(n.to_string())
+            }
+        }
+        serde_json::Value::String(s) => Value::String(s),
+        serde_json::Value::Array(a) => {
+            Value::List(a.into_iter().map(convert_json_value_to_value).collect())
+        }
+        serde_json::Value::Object(o) => {
+            let mut entries = IndexMap::new();
+            for (k, v) in o {
+                entries.insert(k, convert_json_value_to_value(v));
+            }
+            Value::Row(Dictionary::new(entries))
+        }
+    }
+}
+
+pub fn value_to_json_value(v: &Value) -> Result<serde_json::Value, ShellError> {
+    Ok(match v {
+        Value::Nothing => serde_json::Value::Null,
+        Value::Int(i) => match i.to_i64() {
+            Some(i) => serde_json::Value::from(i),
+            None => serde_json::Value::String(i.to_string()),
+        },
+        Value::Number(n) => n
+            .to_f64()
+            .and_then(serde_json::Number::from_f64)
+            .map(serde_json::Value::Number)
+            .unwrap_or_else(|| serde_json::Value::String(n.to_string())),
+        Value::String(s) | Value::Pattern(s) => serde_json::Value::String(s.clone()),
+        Value::Path(p) => serde_json::Value::String(p.to_string_lossy().to_string()),
+        Value::Boolean(b) => serde_json::Value::Bool(*b),
+        Value::Date(d) => serde_json::Value::String(d.to_rfc3339()),
+        Value::Block(b) => serde_json::Value::String(b.text().to_string()),
+        Value::List(l) => serde_json::Value::Array(
+            l.iter()
+                .map(value_to_json_value)
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+        Value::Row(d) => {
+            let mut map = serde_json::Map::new();
+            for (k, v) in d.iter() {
+                map.insert(k.clone(), value_to_json_value(v)?);
+            }
+            serde_json::Value::Object(map)
+        }
+    })
+}
+
+pub fn value_to_json_string(v: &Value, pretty: bool) -> Result<String, ShellError> {
+    let json = value_to_json_value(v)?;
+    if pretty {
+        serde_json::to_string_pretty(&json)
+    } else {
+        serde_json::to_string(&json)
+    }
+    .map_err(|e| ShellError::runtime_error(format!("Could not convert to JSON: {}", e)))
+}
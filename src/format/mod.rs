@@ -0,0 +1,81 @@
+use std::path::Path;
+
+use crate::error::ShellError;
+use crate::evaluate::Value;
+
+pub mod csv;
+pub mod ini;
+pub mod json;
+pub mod toml;
+
+/// The structured file formats the shell knows how to read and write
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Format {
+    Json,
+    Toml,
+    Csv,
+    Tsv,
+    Ini,
+}
+
+impl Format {
+    /// Detects the format from the extension of a file name
+    pub fn from_path(path: &Path) -> Option<Format> {
+        let extension = path.extension()?.to_string_lossy().to_lowercase();
+        match extension.as_str() {
+            "json" => Some(Format::Json),
+            "toml" => Some(Format::Toml),
+            "csv" => Some(Format::Csv),
+            "tsv" => Some(Format::Tsv),
+            "ini" => Some(Format::Ini),
+            _ => None,
+        }
+    }
+
+    /// Parses the contents of a file into a value
+    pub fn parse(self, contents: &str) -> Result<Value, ShellError> {
+        match self {
+            Format::Json => json::from_json_string_to_value(contents),
+            Format::Toml => toml::from_toml_string_to_value(contents),
+            Format::Csv => csv::from_csv_string_to_value(contents, b',', false),
+            Format::Tsv => csv::from_csv_string_to_value(contents, b'\t', false),
+            Format::Ini => ini::from_ini_string_to_value(contents),
+        }
+    }
+
+    /// Serializes the values of a pipeline. Formats that hold a single document expect exactly one
+    /// value, while JSON wraps several values into an array. A list read from a document comes as
+    /// a single `Value::List`, JSON saves it as an array and the other formats take its items.
+    pub fn serialize(self, mut values: Vec<Value>) -> Result<String, ShellError> {
+        if self != Format::Json {
+            if let [Value::List(items)] = values.as_slice() {
+                values = items.clone();
+            }
+        }
+        match self {
+            Format::Csv => csv::values_to_csv_string(&values, b','),
+            Format::Tsv => csv::values_to_csv_string(&values, b'\t'),
+            Format::Json => {
+                let value = if values.len() == 1 {
+                    values.remove(0)
+                } else {
+                    Value::List(values)
+                };
+                json::value_to_json_string(&value, true)
+            }
+            Format::Toml | Format::Ini => {
+                if values.len() != 1 {
+                    return Err(ShellError::runtime_error(format!(
+                        "expected exactly one row to save, found {}",
+                        values.len()
+                    )));
+                }
+                if self == Format::Toml {
+                    toml::value_to_toml_string(&values[0])
+                } else {
+                    ini::value_to_ini_string(&values[0])
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use bigdecimal::{FromPrimitive, ToPrimitive};
+use indexmap::IndexMap;
+use num_bigint::BigInt;
+
+use crate::error::ShellError;
+use crate::evaluate::dict::Dictionary;
+use crate::evaluate::Value;
+
+pub fn from_toml_string_to_value(s: &str) -> Result<Value, ShellError> {
+    let toml: toml::Value = toml::from_str(s)
+        .map_err(|e| ShellError::runtime_error(format!("Could not parse as TOML: {}", e)))?;
+    Ok(convert_toml_value_to_value(toml))
+}
+
+pub fn convert_toml_value_to_value(v: toml::Value) -> Value {
+    match v {
+        toml::Value::Boolean(b) => Value::Boolean(b),
+        toml::Value::Integer(i) => Value::Int(BigInt::from(i)),
+        toml::Value::Float(f) => match bigdecimal::BigDecimal::from_f64(f) {
+            Some(d) => Value::Number(d),
+            None => Value::String(f.to_string()),
+        },
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Datetime(dt) => Value::String(dt.to_string()),
+        toml::Value::Array(a) => {
+            Value::List(a.into_iter().map(convert_toml_value_to_value).collect())
+        }
+        toml::Value::Table(t) => {
+            let mut entries = IndexMap::new();
+            for (k, v) in t {
+                entries.insert(k, convert_toml_value_to_value(v));
+            }
+            Value::Row(Dictionary::new(entries))
+        }
+    }
+}
+
+/// Converts a value to its TOML counterpart. `Nothing` has no TOML representation, so it is
+/// only allowed as a table entry, where it is left out.
+pub fn value_to_toml_value(v: &Value) -> Result<toml::Value, ShellError> {
+    Ok(match v {
+        Value::Nothing => {
+            return Err(ShellError::runtime_error(
+                "Could not convert to TOML: nothing can't be represented",
+            ));
+        }
+        Value::Int(i) => match i.to_i64() {
+            Some(i) => toml::Value::Integer(i),
+            None => toml::Value::String(i.to_string()),
+        },
+        Value::Number(n) => match n.to_f64() {
+            Some(f) => toml::Value::Float(f),
+            None => toml::Value::String(n.to_string()),
+        },
+        Value::String(s) | Value::Pattern(s) => toml::Value::String(s.clone()),
+        Value::Path(p) => toml::Value::String(p.to_string_lossy().to_string()),
+        Value::Block(b) => toml::Value::String(b.text().to_string()),
+        Value::Boolean(b) => toml::Value::Boolean(*b),
+        Value::Date(d) => match d.to_rfc3339().parse() {
+            Ok(dt) => toml::Value::Datetime(dt),
+            Err(_) => toml::Value::String(d.to_rfc3339()),
+        },
+        Value::List(l) => toml::Value::Array(
+            l.iter()
+                .map(value_to_toml_value)
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+        Value::Row(d) => {
+            let mut map = toml::map::Map::new();
+            for (k, v) in d.iter() {
+                if let Value::Nothing = v {
+                    continue;
+                }
+                map.insert(k.clone(), value_to_toml_value(v)?);
+            }
+            toml::Value::Table(map)
+        }
+    })
+}
+
+pub fn value_to_toml_string(v: &Value) -> Result<String, ShellError> {
+    match v {
+        Value::Row(_) => toml::to_string_pretty(&value_to_toml_value(v)?)
+            .map_err(|e| ShellError::runtime_error(format!("Could not convert to TOML: {}", e))),
+        _ => Err(ShellError::runtime_error(
+            "Could not convert to TOML: expected a row at the top level",
+        )),
+    }
+}
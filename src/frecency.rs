@@ -0,0 +1,171 @@
+//! The directories changed to in the file system shell, ranked by how often and how recently they
+//! were visited, for `z`. They are kept in `frecency.txt` in the config directory, one
+//! `rank|time|path` line per directory, which is read and written again on every change so that
+//! several shells running at once share it.
+
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::config;
+
+/// When the ranks add up to more than this they are all aged, and the rarely visited directories
+/// are forgotten
+const MAX_RANK_SUM: f64 = 9000.0;
+
+#[derive(Debug, Clone)]
+pub struct Record {
+    pub path: PathBuf,
+    /// How many times the directory was visited, aged over time
+    pub rank: f64,
+    /// When the directory was last visited, in seconds since the epoch
+    pub time: u64,
+}
+
+impl Record {
+    /// The rank weighted by how long ago the directory was last visited
+    pub fn score(&self, now: u64) -> f64 {
+        let age = now.saturating_sub(self.time);
+        let weight = if age < 60 * 60 {
+            4.0
+        } else if age < 24 * 60 * 60 {
+            2.0
+        } else if age < 7 * 24 * 60 * 60 {
+            0.5
+        } else {
+            0.25
+        };
+        self.rank * weight
+    }
+
+    pub fn visited(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.time)
+    }
+
+    /// Whether the fragments occur in the path in order, ignoring case
+    fn matches(&self, fragments: &[String]) -> bool {
+        let path = self.path.to_string_lossy().to_lowercase();
+        let mut rest = path.as_str();
+        for fragment in fragments {
+            let fragment = fragment.to_lowercase();
+            match rest.find(&fragment) {
+                Some(index) => rest = &rest[index + fragment.len()..],
+                None => return false,
+            }
+        }
+        true
+    }
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+fn database_path() -> Option<PathBuf> {
+    config::config_dir().map(|dir| dir.join("frecency.txt"))
+}
+
+fn load(path: &Path) -> Vec<Record> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(_) => return vec![],
+    };
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, '|');
+            let rank: f64 = parts.next()?.parse().ok()?;
+            // `NaN` and `inf` parse too, such a line was edited by hand or is corrupt
+            if !rank.is_finite() {
+                return None;
+            }
+            let time = parts.next()?.parse().ok()?;
+            let path = PathBuf::from(parts.next()?);
+            Some(Record { path, rank, time })
+        })
+        .collect()
+}
+
+fn save(path: &Path, records: &[Record]) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut contents = String::new();
+    for record in records {
+        contents.push_str(&format!(
+            "{}|{}|{}\n",
+            record.rank,
+            record.time,
+            record.path.display()
+        ));
+    }
+    // written aside and renamed so that another shell never reads half of it
+    let temporary = path.with_extension("txt.tmp");
+    std::fs::write(&temporary, contents)?;
+    std::fs::rename(temporary, path)
+}
+
+/// Records a visit to a directory. Failing to write the database doesn't fail the `cd`.
+pub fn add(dir: &Path) {
+    let path = match database_path() {
+        Some(path) => path,
+        None => return,
+    };
+    let mut records = load(&path);
+    let now = now();
+    match records.iter_mut().find(|record| record.path == dir) {
+        Some(record) => {
+            record.rank += 1.0;
+            record.time = now;
+        }
+        None => records.push(Record {
+            path: dir.to_path_buf(),
+            rank: 1.0,
+            time: now,
+        }),
+    }
+    if records.iter().map(|record| record.rank).sum::<f64>() > MAX_RANK_SUM {
+        for record in &mut records {
+            record.rank *= 0.99;
+        }
+        records.retain(|record| record.rank >= 1.0);
+    }
+    let _ = save(&path, &records);
+}
+
+/// The directories matching all the fragments, the best first. Directories that no longer exist
+/// are removed from the database.
+pub fn query(fragments: &[String]) -> Vec<Record> {
+    let path = match database_path() {
+        Some(path) => path,
+        None => return vec![],
+    };
+    let mut records = load(&path);
+    let count = records.len();
+    records.retain(|record| record.path.is_dir());
+    if records.len() != count {
+        let _ = save(&path, &records);
+    }
+    let now = now();
+    let mut records = records
+        .into_iter()
+        .filter(|record| record.matches(fragments))
+        .collect::<Vec<_>>();
+    records.sort_by(|a, b| {
+        b.score(now)
+            .partial_cmp(&a.score(now))
+            .unwrap_or(Ordering::Equal)
+    });
+    records
+}
+
+/// The best directory matching the fragments other than the current one
+pub fn best(fragments: &[String], current: &Path) -> Option<PathBuf> {
+    query(fragments)
+        .into_iter()
+        .map(|record| record.path)
+        .find(|path| path != current)
+}
@@ -0,0 +1,519 @@
+//! The job table: pipelines started in the background with `&` or stopped with Ctrl+Z. On unix
+//! every job runs in its own process group, which is given the terminal while it is in the
+//! foreground so that Ctrl+Z and Ctrl+C go to the job and not to the shell.
+
+use std::process::{Child, Command};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+use parking_lot::Mutex;
+
+use crate::error::{ErrorKind, ShellError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobStatus {
+    Running,
+    Stopped,
+}
+
+impl JobStatus {
+    pub fn name(self) -> &'static str {
+        match self {
+            JobStatus::Running => "running",
+            JobStatus::Stopped => "stopped",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Job {
+    /// The text of the pipeline
+    pub command: String,
+    pub status: JobStatus,
+    processes: Vec<Process>,
+}
+
+#[derive(Debug)]
+struct Process {
+    child: Child,
+    /// The exit code once the process finished, the negated signal if it was killed by one
+    exit: Option<i32>,
+}
+
+impl Job {
+    pub fn new(command: impl Into<String>, children: Vec<Child>) -> Job {
+        Job {
+            command: command.into(),
+            status: JobStatus::Running,
+            processes: children
+                .into_iter()
+                .map(|child| Process { child, exit: None })
+                .collect(),
+        }
+    }
+
+    /// The process group of the job, which is the pid of its first process
+    pub fn pgid(&self) -> u32 {
+        self.processes
+            .first()
+            .map_or(0, |process| process.child.id())
+    }
+
+    fn is_done(&self) -> bool {
+        self.processes.iter().all(|process| process.exit.is_some())
+    }
+
+    /// Whether a process was killed by Ctrl+C
+    fn is_interrupted(&self) -> bool {
+        self.processes
+            .iter()
+            .any(|process| process.exit == Some(-sys::SIGINT))
+    }
+
+    /// The exit code of the last process, like the status of a pipeline in other shells
+    fn exit_code(&self) -> i32 {
+        self.processes
+            .last()
+            .and_then(|process| process.exit)
+            .unwrap_or(0)
+    }
+}
+
+/// The jobs by their number, shared by every clone of the context like the registry
+#[derive(Clone)]
+pub struct Jobs {
+    jobs: Arc<Mutex<IndexMap<usize, Job>>>,
+    /// The process group in the foreground, 0 while the shell itself is
+    foreground: Arc<AtomicU32>,
+    /// The interrupt flag of the context, set when Ctrl+C kills a foreground process
+    ctrl_c: Arc<AtomicBool>,
+}
+
+impl Jobs {
+    pub fn new(ctrl_c: Arc<AtomicBool>) -> Jobs {
+        Jobs {
+            jobs: Arc::new(Mutex::new(IndexMap::new())),
+            foreground: Arc::new(AtomicU32::new(0)),
+            ctrl_c,
+        }
+    }
+
+    /// Handles Ctrl+C received by the shell: sets the interrupt flag and passes the signal on to
+    /// the process group in the foreground. That only happens when the shell has no terminal, or
+    /// the signal came from `kill`, otherwise the terminal sends it to the process group directly.
+    pub fn interrupt(&self) {
+        self.ctrl_c.store(true, Ordering::SeqCst);
+        let pgid = self.foreground.load(Ordering::SeqCst);
+        if pgid != 0 {
+            let _ = sys::signal_pgid(pgid, sys::SIGINT);
+        }
+    }
+
+    /// Runs `f` with the process group `pgid` in the foreground, holding the terminal
+    pub fn foreground<T>(&self, pgid: u32, f: impl FnOnce() -> T) -> T {
+        sys::give_terminal(pgid);
+        self.foreground.store(pgid, Ordering::SeqCst);
+        let result = f();
+        self.foreground.store(0, Ordering::SeqCst);
+        sys::take_terminal();
+        result
+    }
+
+    /// Adds a job under the lowest free number and returns it
+    pub fn add(&self, job: Job) -> usize {
+        let mut jobs = self.jobs.lock();
+        let id = (1..).find(|id| !jobs.contains_key(id)).unwrap_or(1);
+        jobs.insert(id, job);
+        id
+    }
+
+    /// The number, process group, status and command of every job
+    pub fn list(&self) -> Vec<(usize, u32, JobStatus, String)> {
+        self.jobs
+            .lock()
+            .iter()
+            .map(|(id, job)| (*id, job.pgid(), job.status, job.command.clone()))
+            .collect()
+    }
+
+    /// Removes a job to bring it to the foreground, the last one if no number is given
+    pub fn take(&self, id: Option<usize>) -> Result<(usize, Job), ShellError> {
+        let mut jobs = self.jobs.lock();
+        let id = match id {
+            Some(id) => id,
+            None => *jobs
+                .keys()
+                .last()
+                .ok_or_else(|| ShellError::new(ErrorKind::NotFound, "There are no jobs"))?,
+        };
+        jobs.shift_remove(&id)
+            .map(|job| (id, job))
+            .ok_or_else(|| no_such_job(id))
+    }
+
+    /// Continues a stopped job in the background
+    pub fn resume(&self, id: Option<usize>) -> Result<(usize, String), ShellError> {
+        let mut jobs = self.jobs.lock();
+        let id = match id {
+            Some(id) => id,
+            None => {
+                *jobs
+                    .iter()
+                    .rev()
+                    .find(|(_, job)| job.status == JobStatus::Stopped)
+                    .ok_or_else(|| {
+                        ShellError::new(ErrorKind::NotFound, "There are no stopped jobs")
+                    })?
+                    .0
+            }
+        };
+        let job = jobs.get_mut(&id).ok_or_else(|| no_such_job(id))?;
+        sys::signal_group(job, sys::SIGCONT)?;
+        job.status = JobStatus::Running;
+        Ok((id, job.command.clone()))
+    }
+
+    /// Sends a signal to every process of a job, a stopped job is continued so that it can handle
+    /// the signal
+    pub fn signal(&self, id: usize, signal: i32) -> Result<(), ShellError> {
+        let mut jobs = self.jobs.lock();
+        let job = jobs.get_mut(&id).ok_or_else(|| no_such_job(id))?;
+        sys::signal_group(job, signal)?;
+        if job.status == JobStatus::Stopped && signal != sys::SIGSTOP {
+            sys::signal_group(job, sys::SIGCONT)?;
+            job.status = JobStatus::Running;
+        }
+        Ok(())
+    }
+
+    /// Checks the background jobs without blocking, removing the finished ones. Returns the
+    /// messages to show at the next prompt.
+    pub fn poll(&self) -> Vec<String> {
+        let mut jobs = self.jobs.lock();
+        let mut messages = vec![];
+        for (_, job) in jobs.iter_mut() {
+            sys::update(job, false);
+        }
+        jobs.retain(|id, job| {
+            if job.is_done() {
+                messages.push(match job.exit_code() {
+                    0 => format!("[{}] done\t{}", id, job.command),
+                    code if code < 0 => format!("[{}] killed\t{}", id, job.command),
+                    code => format!("[{}] exit {}\t{}", id, code, job.command),
+                });
+                false
+            } else {
+                true
+            }
+        });
+        messages
+    }
+
+    /// Gives a job the terminal and waits for it, continuing it first if it was stopped. A job
+    /// stopped with Ctrl+Z is put back in the table, under its old number if it had one, and a
+    /// job killed by Ctrl+C sets the interrupt flag so that the rest of the line isn't run.
+    pub fn wait_foreground(&self, mut job: Job, id: Option<usize>) -> Result<(), ShellError> {
+        self.foreground(job.pgid(), || {
+            if job.status == JobStatus::Stopped {
+                sys::signal_group(&mut job, sys::SIGCONT)?;
+            }
+            sys::update(&mut job, true);
+            Ok::<_, ShellError>(())
+        })?;
+        if job.is_interrupted() {
+            self.ctrl_c.store(true, Ordering::SeqCst);
+        }
+        if job.status == JobStatus::Stopped && !job.is_done() {
+            let command = job.command.clone();
+            let id = match id {
+                Some(id) => {
+                    self.jobs.lock().insert(id, job);
+                    id
+                }
+                None => self.add(job),
+            };
+            println!("\n[{}] stopped\t{}", id, command);
+        }
+        Ok(())
+    }
+}
+
+fn no_such_job(id: usize) -> ShellError {
+    ShellError::new(ErrorKind::NotFound, format!("No such job: %{}", id))
+}
+
+/// Prepares the shell for job control when it runs on a terminal
+pub fn init() {
+    sys::init();
+}
+
+/// Puts an external command in the process group `pgid`, or a new one if it is 0. Unless it is
+/// `stoppable` the command ignores Ctrl+Z, for commands whose output the shell is reading. A
+/// `foreground` command takes the terminal itself before it starts, so that it can read it at once.
+pub fn set_process_group(command: &mut Command, pgid: u32, stoppable: bool, foreground: bool) {
+    sys::set_process_group(command, pgid, stoppable, foreground);
+}
+
+/// Puts a command just spawned in its process group from the shell as well, since either the
+/// shell or the command may run first
+pub fn join_process_group(pid: u32, pgid: u32) {
+    sys::join_process_group(pid, pgid);
+}
+
+/// Parses a job given as `%N` or as its number
+pub fn parse_job(text: &str) -> Option<usize> {
+    text.strip_prefix('%').unwrap_or(text).parse().ok()
+}
+
+/// Sends a signal to a single process
+pub fn kill_process(pid: u32, signal: i32) -> Result<(), ShellError> {
+    sys::kill_process(pid, signal)
+}
+
+/// Parses a signal given by name, with or without `SIG`, or by number
+pub fn parse_signal(name: &str) -> Option<i32> {
+    if let Ok(number) = name.parse() {
+        return Some(number);
+    }
+    let name = name.to_uppercase();
+    let name = name.strip_prefix("SIG").unwrap_or(&name);
+    sys::SIGNALS
+        .iter()
+        .find(|(signal, _)| *signal == name)
+        .map(|(_, number)| *number)
+}
+
+#[cfg(unix)]
+mod sys {
+    use std::os::unix::process::CommandExt;
+    use std::process::Command;
+
+    use super::{Job, JobStatus};
+    use crate::error::ShellError;
+
+    pub use libc::{SIGCONT, SIGINT, SIGSTOP};
+
+    pub const SIGNALS: &[(&str, i32)] = &[
+        ("HUP", libc::SIGHUP),
+        ("INT", libc::SIGINT),
+        ("QUIT", libc::SIGQUIT),
+        ("KILL", libc::SIGKILL),
+        ("USR1", libc::SIGUSR1),
+        ("USR2", libc::SIGUSR2),
+        ("TERM", libc::SIGTERM),
+        ("CONT", libc::SIGCONT),
+        ("STOP", libc::SIGSTOP),
+        ("TSTP", libc::SIGTSTP),
+    ];
+
+    fn is_interactive() -> bool {
+        unsafe { libc::isatty(libc::STDIN_FILENO) == 1 }
+    }
+
+    pub fn init() {
+        if !is_interactive() {
+            return;
+        }
+        unsafe {
+            // the shell itself must not be stopped when it hands the terminal around
+            libc::signal(libc::SIGTSTP, libc::SIG_IGN);
+            libc::signal(libc::SIGTTOU, libc::SIG_IGN);
+            libc::signal(libc::SIGTTIN, libc::SIG_IGN);
+            libc::setpgid(0, 0);
+            libc::tcsetpgrp(libc::STDIN_FILENO, libc::getpgrp());
+        }
+    }
+
+    pub fn set_process_group(command: &mut Command, pgid: u32, stoppable: bool, foreground: bool) {
+        let pgid = pgid as libc::pid_t;
+        let foreground = foreground && is_interactive();
+        unsafe {
+            command.pre_exec(move || {
+                libc::setpgid(0, pgid);
+                if foreground {
+                    // stdin may already be a pipe, any of the standard streams on the terminal will
+                    // do. SIGTTOU is still ignored here as in the shell.
+                    let terminal = [libc::STDIN_FILENO, libc::STDOUT_FILENO, libc::STDERR_FILENO]
+                        .iter()
+                        .copied()
+                        .find(|&fd| libc::isatty(fd) == 1);
+                    if let Some(fd) = terminal {
+                        libc::tcsetpgrp(fd, libc::getpgrp());
+                    }
+                }
+                // ignored signals stay ignored across exec
+                if stoppable {
+                    libc::signal(libc::SIGTSTP, libc::SIG_DFL);
+                }
+                libc::signal(libc::SIGTTOU, libc::SIG_DFL);
+                libc::signal(libc::SIGTTIN, libc::SIG_DFL);
+                Ok(())
+            });
+        }
+    }
+
+    pub fn join_process_group(pid: u32, pgid: u32) {
+        let pgid = if pgid == 0 { pid } else { pgid };
+        // fails harmlessly when the command has already exec'd, it is in the group by then
+        unsafe {
+            libc::setpgid(pid as libc::pid_t, pgid as libc::pid_t);
+        }
+    }
+
+    pub fn give_terminal(pgid: u32) {
+        if is_interactive() {
+            unsafe {
+                libc::tcsetpgrp(libc::STDIN_FILENO, pgid as libc::pid_t);
+            }
+        }
+    }
+
+    pub fn take_terminal() {
+        if is_interactive() {
+            unsafe {
+                libc::tcsetpgrp(libc::STDIN_FILENO, libc::getpgrp());
+            }
+        }
+    }
+
+    pub fn signal_group(job: &mut Job, signal: i32) -> Result<(), ShellError> {
+        signal_pgid(job.pgid(), signal)
+    }
+
+    pub fn signal_pgid(pgid: u32, signal: i32) -> Result<(), ShellError> {
+        if unsafe { libc::killpg(pgid as libc::pid_t, signal) } == 0 {
+            Ok(())
+        } else {
+            Err(std::io::Error::last_os_error().into())
+        }
+    }
+
+    pub fn kill_process(pid: u32, signal: i32) -> Result<(), ShellError> {
+        if unsafe { libc::kill(pid as libc::pid_t, signal) } == 0 {
+            Ok(())
+        } else {
+            Err(std::io::Error::last_os_error().into())
+        }
+    }
+
+    /// Collects the status changes of the processes of a job. When blocking, waits until every
+    /// process finished or one of them stopped.
+    pub fn update(job: &mut Job, block: bool) {
+        let options = if block {
+            libc::WUNTRACED
+        } else {
+            libc::WUNTRACED | libc::WCONTINUED | libc::WNOHANG
+        };
+        if block {
+            job.status = JobStatus::Running;
+        }
+        for process in job.processes.iter_mut() {
+            while process.exit.is_none() {
+                let mut status = 0;
+                let pid = unsafe {
+                    libc::waitpid(process.child.id() as libc::pid_t, &mut status, options)
+                };
+                if pid < 0 {
+                    if std::io::Error::last_os_error().kind() == std::io::ErrorKind::Interrupted {
+                        continue;
+                    }
+                    // already collected by somebody else
+                    process.exit = Some(0);
+                } else if pid == 0 {
+                    break;
+                } else if libc::WIFEXITED(status) {
+                    process.exit = Some(libc::WEXITSTATUS(status));
+                } else if libc::WIFSIGNALED(status) {
+                    process.exit = Some(-libc::WTERMSIG(status));
+                } else if libc::WIFSTOPPED(status) {
+                    job.status = JobStatus::Stopped;
+                    break;
+                } else if libc::WIFCONTINUED(status) {
+                    job.status = JobStatus::Running;
+                }
+                if !block {
+                    break;
+                }
+            }
+            if block && job.status == JobStatus::Stopped {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod sys {
+    use std::process::Command;
+
+    use super::Job;
+    use crate::error::{ErrorKind, ShellError};
+
+    pub const SIGINT: i32 = 2;
+    pub const SIGCONT: i32 = 18;
+    pub const SIGSTOP: i32 = 19;
+
+    pub const SIGNALS: &[(&str, i32)] = &[("INT", 2), ("KILL", 9), ("TERM", 15)];
+
+    pub fn init() {}
+
+    pub fn set_process_group(
+        _command: &mut Command,
+        _pgid: u32,
+        _stoppable: bool,
+        _foreground: bool,
+    ) {
+    }
+
+    pub fn join_process_group(_pid: u32, _pgid: u32) {}
+
+    pub fn give_terminal(_pgid: u32) {}
+
+    pub fn take_terminal() {}
+
+    /// The console sends Ctrl+C to every process attached to it
+    pub fn signal_pgid(_pgid: u32, _signal: i32) -> Result<(), ShellError> {
+        Ok(())
+    }
+
+    /// Without process groups a job can only be killed
+    pub fn signal_group(job: &mut Job, signal: i32) -> Result<(), ShellError> {
+        if signal == SIGCONT {
+            return Ok(());
+        }
+        if signal == SIGSTOP {
+            return Err(ShellError::new(
+                ErrorKind::InvalidInput,
+                "Jobs can't be stopped on this platform",
+            ));
+        }
+        for process in job.processes.iter_mut() {
+            let _ = process.child.kill();
+        }
+        Ok(())
+    }
+
+    pub fn kill_process(_pid: u32, _signal: i32) -> Result<(), ShellError> {
+        Err(ShellError::new(
+            ErrorKind::InvalidInput,
+            "Processes can only be killed as jobs on this platform",
+        ))
+    }
+
+    pub fn update(job: &mut Job, block: bool) {
+        for process in job.processes.iter_mut() {
+            if process.exit.is_some() {
+                continue;
+            }
+            let status = if block {
+                process.child.wait().ok()
+            } else {
+                process.child.try_wait().ok().flatten()
+            };
+            if let Some(status) = status {
+                process.exit = Some(status.code().unwrap_or(-1));
+            }
+        }
+    }
+}
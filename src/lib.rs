@@ -1,9 +1,16 @@
 pub mod cli;
 pub mod commands;
+pub mod config;
 pub mod context;
 pub mod deserializer;
 pub mod error;
 pub mod evaluate;
+pub mod expand;
+pub mod format;
+pub mod frecency;
+pub mod jobs;
 pub mod parser;
+pub mod path_cache;
 pub mod shell;
 pub mod signature;
+pub mod suggestions;
@@ -1,7 +1,16 @@
+use crate::evaluate::scope::Scope;
+use crate::evaluate::Value;
 use crate::parser::span::{HasSpan, Span};
 use crate::parser::token::{SpannedToken, Token};
 
-pub type ExternalArg = String;
+/// An argument of an external command. Unless it was quoted or is the value of a variable, it is
+/// expanded like a glob when the command runs.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ExternalArg {
+    pub arg: String,
+    pub expand: bool,
+    pub span: Span,
+}
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct ExternalArgs {
@@ -10,24 +19,43 @@ pub struct ExternalArgs {
 }
 
 impl ExternalArgs {
-    pub fn iter(&self) -> impl Iterator<Item=&ExternalArg> {
+    pub fn iter(&self) -> impl Iterator<Item = &ExternalArg> {
         self.list.iter()
     }
-    pub fn from_tokens(tokens: &mut impl Iterator<Item=SpannedToken>, source: &str, span: Span) -> Self {
+    /// Collects the arguments of an external command. Variables bound in the scope are replaced by
+    /// their value, the others are expanded from the environment when the command runs.
+    pub fn from_tokens(
+        tokens: &mut impl Iterator<Item = SpannedToken>,
+        source: &str,
+        scope: &Scope,
+        span: Span,
+    ) -> Self {
         let list = tokens
-            .map(|spanned| match spanned.item {
-                Token::String(s) => Some(s.string(source)),
-                Token::Bare | Token::ExternalWord | Token::Flag(_) | Token::GlobPattern => {
-                    Some(spanned.span.string(source))
-                }
-                Token::Pipeline(_) | Token::Separator | Token::Whitespace => None,
+            .filter_map(|spanned| {
+                let (arg, expand) = match spanned.item {
+                    Token::String(s) => (s.string(source), false),
+                    Token::Bare | Token::ExternalWord | Token::Flag(_) | Token::GlobPattern => {
+                        let word = spanned.span.string(source);
+                        let word = shellexpand::env_with_context_no_errors(&word, |name| {
+                            scope.lookup(name).map(Value::to_string)
+                        });
+                        (word.to_string(), true)
+                    }
+                    Token::Variable(path) => match scope.lookup(path.slice(source)) {
+                        Some(value) => (value.to_string(), false),
+                        None => (spanned.span.string(source), true),
+                    },
+                    Token::Block(..) | Token::List(_) => (spanned.span.string(source), false),
+                    Token::Pipeline(_) | Token::Separator | Token::Whitespace => return None,
+                };
+                Some(ExternalArg {
+                    arg,
+                    expand,
+                    span: spanned.span,
+                })
             })
-            .flatten()
             .collect::<Vec<_>>();
-        Self {
-            list,
-            span,
-        }
+        Self { list, span }
     }
 }
 
@@ -38,15 +38,25 @@ impl HasSpan for ClassifiedCommand {
 #[derive(Debug, Clone)]
 pub struct ClassifiedPipeline {
     pub commands: Commands,
+    /// The text the spans of the commands point into, which is the line after alias expansion
+    pub source: String,
+    /// Set by a trailing `&`, the commands are started as a job without waiting for them
+    pub background: bool,
 }
 
 impl ClassifiedPipeline {
-    pub fn commands(list: Vec<ClassifiedCommand>, span: impl Into<Span>) -> ClassifiedPipeline {
+    pub fn commands(
+        list: Vec<ClassifiedCommand>,
+        span: impl Into<Span>,
+        source: impl Into<String>,
+    ) -> ClassifiedPipeline {
         ClassifiedPipeline {
             commands: Commands {
                 list,
                 span: span.into(),
             },
+            source: source.into(),
+            background: false,
         }
     }
 }
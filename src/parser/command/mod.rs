@@ -19,7 +19,13 @@ pub fn parse_command_tail(
     let mut rest_signature = config.clone();
     while let Some(spanned) = tail.next() {
         match spanned.item {
-            Token::String(_) | Token::Bare | Token::ExternalWord | Token::GlobPattern => {
+            Token::String(_)
+            | Token::Bare
+            | Token::ExternalWord
+            | Token::GlobPattern
+            | Token::Variable(_)
+            | Token::Block(..)
+            | Token::List(_) => {
                 if !rest_signature.positional.is_empty() {
                     positional.push(spanned);
                     rest_signature.shift_positional();
@@ -36,7 +42,7 @@ pub fn parse_command_tail(
                             named.insert_switch(flag.slice(source), Some(flag));
                         }
                         NamedType::Mandatory(_) => {
-                            if let Some(next_token) = tail.next() {
+                            if let Some(next_token) = next_value(tail) {
                                 rest_signature.remove_named(flag.slice(source));
                                 named.insert_mandatory(flag.slice(source), next_token);
                             } else {
@@ -44,7 +50,7 @@ pub fn parse_command_tail(
                             }
                         }
                         NamedType::Optional(_) => {
-                            let next_token = tail.next();
+                            let next_token = next_value(tail);
                             rest_signature.remove_named(flag.slice(source));
                             named.insert_optional(flag.slice(source), next_token);
                         }
@@ -82,7 +88,9 @@ pub fn parse_command_tail(
         }
     }
     if let Some((span, reason)) = err {
-        Err(ProximateShellError::ParseError(span, reason).start())
+        Err(ProximateShellError::ParseError(span, reason)
+            .start()
+            .with_help(format!("run `help {}` to see its parameters", config.name)))
     } else {
         let positional = if positional.is_empty() {
             None
@@ -94,10 +102,15 @@ pub fn parse_command_tail(
         } else {
             Some(named)
         };
-        if positional.is_none() && positional.is_none() {
+        if positional.is_none() && named.is_none() {
             Ok(None)
         } else {
             Ok(Some((positional, named)))
         }
     }
 }
+
+/// Takes the next token that can be a value, skipping over whitespace
+fn next_value(tail: &mut impl Iterator<Item = SpannedToken>) -> Option<SpannedToken> {
+    tail.find(|token| !matches!(token.item, Token::Whitespace | Token::Separator))
+}
@@ -2,7 +2,7 @@ use enumflags2::BitFlags;
 use nom::branch::alt;
 use nom::bytes::complete::{tag, take_while1};
 use nom::character::complete::{char, none_of, space1};
-use nom::combinator::opt;
+use nom::combinator::{map, opt};
 #[allow(unused)]
 use nom::error::ParseError;
 use nom::multi::{many0, many1};
@@ -12,7 +12,7 @@ use nom_tracable::tracable_parser;
 
 pub use parse_pipeline as parse;
 use pipeline::{Pipeline, PipelineElement};
-use span::{Span, Spanned, SpannedItem};
+use span::{span_for_spanned_list, Span, Spanned, SpannedItem};
 use token::{SpannedToken, Token};
 use tracable::{nom_input, NomSpan};
 
@@ -28,11 +28,39 @@ pub mod tracable;
 
 pub fn parse_pipeline(input: &str) -> Result<SpannedToken, ShellError> {
     match pipeline(nom_input(input)) {
-        Ok((_rest, val)) => Ok(val),
+        Ok((_rest, mut val)) => {
+            if let Token::Pipeline(pipeline) = &mut val.item {
+                take_background(pipeline, input);
+            }
+            Ok(val)
+        }
         Err(err) => Err(ShellError::parse_error(err)),
     }
 }
 
+/// Removes a trailing `&` word from the last element of the pipeline and marks the pipeline as a
+/// background job instead. Only the whole line can be put in the background, not a block.
+fn take_background(pipeline: &mut Pipeline, source: &str) {
+    let tokens = match pipeline.parts.last_mut() {
+        Some(element) => &mut element.tokens,
+        None => return,
+    };
+    while let Some(Token::Whitespace) = tokens.item.last().map(|token| &token.item) {
+        tokens.item.pop();
+    }
+    match tokens.item.last() {
+        Some(token) if token.span.slice(source) == "&" && tokens.item.len() > 1 => {
+            pipeline.background = Some(token.span);
+            tokens.item.pop();
+            while let Some(Token::Whitespace) = tokens.item.last().map(|token| &token.item) {
+                tokens.item.pop();
+            }
+            tokens.span = span_for_spanned_list(tokens.item.iter().map(|token| token.span));
+        }
+        _ => {}
+    }
+}
+
 #[tracable_parser]
 pub fn dq_string(input: NomSpan) -> IResult<NomSpan, SpannedToken> {
     let start = input.offset;
@@ -279,9 +307,181 @@ pub fn external_word(input: NomSpan) -> IResult<NomSpan, SpannedToken> {
     Ok((input, Token::ExternalWord.spanned(Span::new(start, end))))
 }
 
+/// A word with alternatives in braces like `{a,b}` or `src/{main,lib}.rs`, which an external
+/// command gets expanded like a glob. Braces without a comma start a block.
+#[tracable_parser]
+pub fn brace_word(input: NomSpan) -> IResult<NomSpan, SpannedToken> {
+    let start = input.offset;
+    let (rest, word) =
+        take_while1(|c| is_external_word_char(c) || c == '{' || c == '}')(input)?;
+    if !has_brace_alternatives(word.fragment) {
+        return Err(nom::Err::Error(nom::error::ParseError::from_error_kind(
+            input,
+            nom::error::ErrorKind::TakeWhile1,
+        )));
+    }
+    let end = rest.offset;
+
+    Ok((rest, Token::GlobPattern.spanned(Span::new(start, end))))
+}
+
+/// Whether a word has a pair of braces with a comma directly inside
+fn has_brace_alternatives(word: &str) -> bool {
+    let mut open = vec![];
+    for c in word.chars() {
+        match c {
+            '{' => open.push(false),
+            ',' => {
+                if let Some(comma) = open.last_mut() {
+                    *comma = true;
+                }
+            }
+            // the brace is popped whether or not it had alternatives
+            '}' if open.pop() == Some(true) => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+/// A word with environment variables in it like `$HOME/src` or `v${VERSION}`, expanded when it
+/// is given as a path. A variable alone like `$it.name` is left to `variable`.
+#[tracable_parser]
+pub fn env_word(input: NomSpan) -> IResult<NomSpan, SpannedToken> {
+    let start = input.offset;
+    let fragment = input.fragment;
+    let mut len = 0;
+    let mut chars = fragment.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        if c == '$' && chars.peek().map(|&(_, next)| next) == Some('{') {
+            match fragment[index..].find('}') {
+                Some(close) => {
+                    len = index + close + 1;
+                    while chars.peek().is_some_and(|&(next, _)| next < len) {
+                        chars.next();
+                    }
+                }
+                None => break,
+            }
+        } else if c == '$' || is_external_word_char(c) {
+            len = index + c.len_utf8();
+        } else {
+            break;
+        }
+    }
+    let word = &fragment[..len];
+    if !word.contains('$') || is_variable_path(word) {
+        return Err(nom::Err::Error(nom::error::ParseError::from_error_kind(
+            input,
+            nom::error::ErrorKind::TakeWhile1,
+        )));
+    }
+
+    Ok((
+        input.slice(len..),
+        Token::ExternalWord.spanned(Span::new(start, start + len)),
+    ))
+}
+
+/// Whether a word is a variable with a column path, like `$it.name`
+fn is_variable_path(word: &str) -> bool {
+    match word.strip_prefix('$') {
+        Some(path) => path
+            .split('.')
+            .all(|member| !member.is_empty() && member.chars().all(is_variable_char)),
+        None => false,
+    }
+}
+
+#[tracable_parser]
+pub fn variable(input: NomSpan) -> IResult<NomSpan, SpannedToken> {
+    let start = input.offset;
+    let (input, _) = char('$')(input)?;
+    let name_start = input.offset;
+    let (input, _) = take_while1(is_variable_char)(input)?;
+    let (input, _) = many0(tuple((char('.'), take_while1(is_variable_char))))(input)?;
+    let end = input.offset;
+
+    Ok((
+        input,
+        Token::Variable(Span::new(name_start, end)).spanned(Span::new(start, end)),
+    ))
+}
+
+#[tracable_parser]
+pub fn block(input: NomSpan) -> IResult<NomSpan, SpannedToken> {
+    let start = input.offset;
+    let (input, _) = char('{')(input)?;
+    let (input, params) = opt(block_params)(input)?;
+    let (input, pipeline) = pipeline_body(input)?;
+    let (input, _) = char('}')(input)?;
+    let end = input.offset;
+
+    Ok((
+        input,
+        Token::Block(params.unwrap_or_default(), pipeline).spanned(Span::new(start, end)),
+    ))
+}
+
+/// The names between bars at the start of a block, eg `|acc, it|`. A pipeline can't start with a
+/// bar, so this doesn't take anything away from the pipeline.
+#[tracable_parser]
+pub fn block_params(input: NomSpan) -> IResult<NomSpan, Vec<Span>> {
+    let (input, _) = opt(any_space)(input)?;
+    let (input, _) = char('|')(input)?;
+    let (input, params) = many0(alt((
+        map(char(','), |_| None),
+        map(whitespace, |_| None),
+        map(take_while1(is_variable_char), |name: NomSpan| {
+            Some(Span::from(name))
+        }),
+    )))(input)?;
+    let (input, _) = char('|')(input)?;
+
+    Ok((input, params.into_iter().flatten().collect()))
+}
+
+#[tracable_parser]
+pub fn list(input: NomSpan) -> IResult<NomSpan, SpannedToken> {
+    let start = input.offset;
+    let (input, _) = char('[')(input)?;
+    let (input, items) = many0(alt((
+        map(char(','), |_| None),
+        map(whitespace, |_| None),
+        map(alt((string, block, list, variable, list_word)), Some),
+    )))(input)?;
+    let (input, _) = char(']')(input)?;
+    let end = input.offset;
+
+    let items = items.into_iter().flatten().collect();
+
+    Ok((input, Token::List(items).spanned(Span::new(start, end))))
+}
+
+/// A word inside of a list, which ends at a comma unlike the words of a command
+#[tracable_parser]
+pub fn list_word(input: NomSpan) -> IResult<NomSpan, SpannedToken> {
+    let start = input.offset;
+    let (input, _) = take_while1(|c| c != ',' && is_external_word_char(c))(input)?;
+    let end = input.offset;
+
+    Ok((input, Token::ExternalWord.spanned(Span::new(start, end))))
+}
+
 #[tracable_parser]
 pub fn node(input: NomSpan) -> IResult<NomSpan, SpannedToken> {
-    let (input, node) = alt((string, flag, filename, pattern, external_word))(input)?;
+    let (input, node) = alt((
+        string,
+        brace_word,
+        block,
+        list,
+        env_word,
+        variable,
+        flag,
+        filename,
+        pattern,
+        external_word,
+    ))(input)?;
 
     Ok((input, node))
 }
@@ -360,8 +560,7 @@ pub fn spaced_token_list(input: NomSpan) -> IResult<NomSpan, Spanned<Vec<Spanned
 #[tracable_parser]
 pub fn pipeline(input: NomSpan) -> IResult<NomSpan, SpannedToken> {
     let start = input.offset;
-    let (input, head) = spaced_token_list(input)?;
-    let (input, items) = many0(tuple((tag("|"), spaced_token_list)))(input)?;
+    let (input, pipeline) = pipeline_body(input)?;
 
     if input.input_len() != 0 {
         return Err(nom::Err::Error((input, nom::error::ErrorKind::Eof)));
@@ -369,6 +568,19 @@ pub fn pipeline(input: NomSpan) -> IResult<NomSpan, SpannedToken> {
 
     let end = input.offset;
 
+    Ok((
+        input,
+        Token::Pipeline(pipeline).spanned(Span::new(start, end)),
+    ))
+}
+
+/// The elements of a pipeline separated by '|', without requiring the whole input to be consumed
+/// so that it can also be used inside of a block
+#[tracable_parser]
+pub fn pipeline_body(input: NomSpan) -> IResult<NomSpan, Pipeline> {
+    let (input, head) = spaced_token_list(input)?;
+    let (input, items) = many0(tuple((tag("|"), spaced_token_list)))(input)?;
+
     let mut all_items: Vec<PipelineElement> = vec![PipelineElement::new(None, head)];
 
     all_items.extend(
@@ -377,10 +589,7 @@ pub fn pipeline(input: NomSpan) -> IResult<NomSpan, SpannedToken> {
             .map(|(pipe, items)| PipelineElement::new(Some(Span::from(pipe)), items)),
     );
 
-    Ok((
-        input,
-        Token::Pipeline(Pipeline::new(all_items)).spanned(Span::new(start, end)),
-    ))
+    Ok((input, Pipeline::new(all_items, None)))
 }
 
 fn is_external_word_char(c: char) -> bool {
@@ -391,6 +600,10 @@ fn is_external_word_char(c: char) -> bool {
     }
 }
 
+fn is_variable_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-'
+}
+
 /// These characters appear in globs and not bare words
 fn is_glob_specific_char(c: char) -> bool {
     c == '*' || c == '?'
@@ -441,6 +654,7 @@ fn is_file_char(c: char) -> bool {
         '~' => true,
         ':' => true,
         '?' => true,
+        '.' => true,
         _ => false,
     }
 }
@@ -5,12 +5,14 @@ use crate::parser::span::HasSpan;
 
 use super::{Span, Spanned, SpannedToken};
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, new, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, new, Serialize, Deserialize)]
 pub struct Pipeline {
     pub parts: Vec<PipelineElement>,
+    /// The span of a trailing `&`, which runs the pipeline as a background job
+    pub background: Option<Span>,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
 pub struct PipelineElement {
     pub pipe: Option<Span>,
     pub tokens: Spanned<Vec<SpannedToken>>,
@@ -17,6 +17,24 @@ pub enum SyntaxShape {
     Path,
     /// A glob pattern is allowed, eg `foo*`
     Pattern,
+    /// A block of code in braces, eg `{ echo $it }`
+    Block,
+}
+
+impl SyntaxShape {
+    /// Looks up a shape by the name used in the parameters of `def`, eg `int` in `[count: int]`
+    pub fn from_name(name: &str) -> Option<SyntaxShape> {
+        match name {
+            "any" => Some(SyntaxShape::Any),
+            "string" => Some(SyntaxShape::String),
+            "number" => Some(SyntaxShape::Number),
+            "int" | "integer" => Some(SyntaxShape::Int),
+            "path" => Some(SyntaxShape::Path),
+            "pattern" => Some(SyntaxShape::Pattern),
+            "block" => Some(SyntaxShape::Block),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for SyntaxShape {
@@ -31,6 +49,7 @@ impl fmt::Display for SyntaxShape {
                 SyntaxShape::Int => "integer",
                 SyntaxShape::Path => "path",
                 SyntaxShape::Pattern => "pattern",
+                SyntaxShape::Block => "block",
             }
         )
     }
@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use super::pipeline::Pipeline;
 use super::span::{Span, Spanned};
 
-#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Deserialize, Serialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
 pub enum Token {
     String(Span),
     Bare,
@@ -12,6 +12,13 @@ pub enum Token {
     Separator,
     GlobPattern,
     ExternalWord,
+    /// A variable like `$it` or `$it.name`, the span covers the name and the column path
+    Variable(Span),
+    /// A pipeline in braces, eg `{ ls | count }`, evaluated later by the command that receives it.
+    /// The spans are the names of its parameters, eg `err` in `{ |err| echo $err.message }`.
+    Block(Vec<Span>, Pipeline),
+    /// A list in square brackets, eg `[a b c]`, the items are separated by spaces or commas
+    List(Vec<SpannedToken>),
     Pipeline(Pipeline),
 }
 
@@ -25,6 +32,9 @@ impl Token {
             Token::Separator => "separator",
             Token::GlobPattern => "glob pattern",
             Token::ExternalWord => "external word",
+            Token::Variable(_) => "variable",
+            Token::Block(..) => "block",
+            Token::List(_) => "list",
             Token::Pipeline(_) => "pipeline",
         }
     }
@@ -0,0 +1,149 @@
+//! The executables in the directories of `PATH`. They are looked up once and kept until `PATH`
+//! changes or `rehash` is run, so that running, completing and highlighting commands doesn't read
+//! the directories again every time.
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+use parking_lot::Mutex;
+
+#[derive(Debug, Default)]
+struct Table {
+    /// The `PATH` the table was built from
+    path: Option<OsString>,
+    /// The executables of each name, in the order of their directories in `PATH`
+    executables: IndexMap<String, Vec<PathBuf>>,
+    /// How many times the commands were run, for `hash`
+    hits: IndexMap<String, usize>,
+}
+
+impl Table {
+    fn build(path: Option<OsString>) -> Table {
+        let mut executables: IndexMap<String, Vec<PathBuf>> = IndexMap::new();
+        for dir in path.iter().flat_map(std::env::split_paths) {
+            let entries = match std::fs::read_dir(dir) {
+                Ok(entries) => entries,
+                Err(_) => continue,
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if !is_executable(&path) {
+                    continue;
+                }
+                for name in names_of(&path) {
+                    executables.entry(name).or_default().push(path.clone());
+                }
+            }
+        }
+        Table {
+            path,
+            executables,
+            hits: IndexMap::new(),
+        }
+    }
+}
+
+/// The table of executables, shared by every clone like the other parts of the context
+#[derive(Debug, Clone, Default)]
+pub struct PathCache {
+    table: Arc<Mutex<Option<Table>>>,
+}
+
+impl PathCache {
+    fn with_table<T>(&self, f: impl FnOnce(&mut Table) -> T) -> T {
+        let path = std::env::var_os("PATH");
+        let mut table = self.table.lock();
+        if !matches!(&*table, Some(table) if table.path == path) {
+            *table = Some(Table::build(path));
+        }
+        f(table.as_mut().expect("the table was just built"))
+    }
+
+    /// Forgets the executables, they are looked up again when needed
+    pub fn rehash(&self) {
+        *self.table.lock() = None;
+    }
+
+    /// The executable a command runs. A name with a separator like `./build.sh` isn't looked up
+    /// in `PATH` but relative to `cwd`.
+    pub fn resolve(&self, name: &str, cwd: &Path) -> Option<PathBuf> {
+        if name.contains(std::path::is_separator) {
+            return which::which_in(name, std::env::var_os("PATH"), cwd).ok();
+        }
+        self.with_table(|table| {
+            table
+                .executables
+                .get(name)
+                .and_then(|paths| paths.first().cloned())
+        })
+    }
+
+    /// Every executable of the name in `PATH`, the one that runs first
+    pub fn resolve_all(&self, name: &str) -> Vec<PathBuf> {
+        self.with_table(|table| table.executables.get(name).cloned().unwrap_or_default())
+    }
+
+    /// The names of all the executables
+    pub fn names(&self) -> Vec<String> {
+        self.with_table(|table| table.executables.keys().cloned().collect())
+    }
+
+    /// Counts a run of a command
+    pub fn hit(&self, name: &str) {
+        self.with_table(|table| *table.hits.entry(name.to_string()).or_insert(0) += 1);
+    }
+
+    /// Adds a command to the ones listed by `hash` without running it, false if it isn't found
+    pub fn remember(&self, name: &str) -> bool {
+        self.with_table(|table| {
+            if !table.executables.contains_key(name) {
+                return false;
+            }
+            table.hits.entry(name.to_string()).or_insert(0);
+            true
+        })
+    }
+
+    /// The commands run since the table was built, with how many times and their executable
+    pub fn hits(&self) -> Vec<(String, usize, PathBuf)> {
+        self.with_table(|table| {
+            table
+                .hits
+                .iter()
+                .filter_map(|(name, hits)| {
+                    let path = table.executables.get(name)?.first()?;
+                    Some((name.clone(), *hits, path.clone()))
+                })
+                .collect()
+        })
+    }
+}
+
+/// The names a command is run by, on windows `git.exe` is also run as `git`
+fn names_of(path: &Path) -> Vec<String> {
+    let mut names = vec![];
+    if let Some(name) = path.file_name() {
+        names.push(name.to_string_lossy().to_string());
+    }
+    if cfg!(windows) {
+        if let Some(stem) = path.file_stem() {
+            names.push(stem.to_string_lossy().to_string());
+        }
+    }
+    names
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path)
+        .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
@@ -0,0 +1,450 @@
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use num_bigint::BigInt;
+
+use crate::commands::cd::CdArgs;
+use crate::commands::cp::CopyArgs;
+use crate::commands::ls::LsArgs;
+use crate::commands::mkdir::MkdirArgs;
+use crate::commands::RunnableContext;
+use crate::error::{ErrorKind, ShellError};
+use crate::evaluate::dict::Dictionary;
+use crate::evaluate::Value;
+use crate::shell::{join, resolve, DirStack};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    Tar,
+    TarGz,
+    Zip,
+}
+
+impl ArchiveKind {
+    /// Recognizes an archive by its extension
+    pub fn from_path(path: &Path) -> Option<ArchiveKind> {
+        let name = path.file_name()?.to_string_lossy().to_lowercase();
+        if name.ends_with(".tar") {
+            Some(ArchiveKind::Tar)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveKind::TarGz)
+        } else if name.ends_with(".zip") {
+            Some(ArchiveKind::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ArchiveEntry {
+    /// The path inside the archive without a leading `/`, eg `bin/app`
+    path: String,
+    kind: &'static str,
+    size: u64,
+    modified: Option<DateTime<Utc>>,
+}
+
+/// A read-only shell over the tree of a tar or zip archive, `cp` extracts entries from it. The
+/// index is read once when entering, the contents when extracting.
+#[derive(Debug, Clone)]
+pub struct ArchiveShell {
+    /// The path of the current directory in the archive, always absolute
+    path: String,
+    archive: PathBuf,
+    kind: ArchiveKind,
+    entries: Vec<ArchiveEntry>,
+    /// The directory the shell was entered from, entries are extracted there
+    cwd: PathBuf,
+    dirs: DirStack,
+}
+
+impl ArchiveShell {
+    pub fn open(
+        archive: PathBuf,
+        kind: ArchiveKind,
+        cwd: impl Into<PathBuf>,
+    ) -> Result<ArchiveShell, ShellError> {
+        let mut entries = vec![];
+        match kind {
+            ArchiveKind::Tar | ArchiveKind::TarGz => {
+                let mut tar = tar::Archive::new(tar_reader(&archive, kind)?);
+                for entry in tar.entries().map_err(|e| ShellError::io(e, &archive))? {
+                    let entry = entry.map_err(|e| ShellError::io(e, &archive))?;
+                    let header = entry.header();
+                    let path = match entry.path().ok().and_then(|path| normalize(&path)) {
+                        Some(path) => path,
+                        None => continue,
+                    };
+                    let entry_type = header.entry_type();
+                    entries.push(ArchiveEntry {
+                        path,
+                        kind: if entry_type.is_dir() {
+                            "dir"
+                        } else if entry_type.is_symlink() {
+                            "symlink"
+                        } else {
+                            "file"
+                        },
+                        size: entry.size(),
+                        modified: header
+                            .mtime()
+                            .ok()
+                            .and_then(|mtime| Utc.timestamp_opt(mtime as i64, 0).single()),
+                    });
+                }
+            }
+            ArchiveKind::Zip => {
+                let mut zip = open_zip(&archive)?;
+                for i in 0..zip.len() {
+                    let file = zip.by_index(i).map_err(|e| zip_error(e, &archive))?;
+                    let path = match normalize(Path::new(file.name())) {
+                        Some(path) => path,
+                        None => continue,
+                    };
+                    let modified = file.last_modified();
+                    entries.push(ArchiveEntry {
+                        path,
+                        kind: if file.is_dir() { "dir" } else { "file" },
+                        size: file.size(),
+                        modified: NaiveDate::from_ymd_opt(
+                            modified.year().into(),
+                            modified.month().into(),
+                            modified.day().into(),
+                        )
+                        .and_then(|date| {
+                            date.and_hms_opt(
+                                modified.hour().into(),
+                                modified.minute().into(),
+                                modified.second().into(),
+                            )
+                        })
+                        .map(|time| Utc.from_utc_datetime(&time)),
+                    });
+                }
+            }
+        }
+        // archives may leave out the directories of their files
+        let mut dirs = vec![];
+        for entry in entries.iter() {
+            let mut parent = entry.path.as_str();
+            while let Some(i) = parent.rfind('/') {
+                parent = &parent[..i];
+                if !dirs.contains(&parent.to_string())
+                    && !entries.iter().any(|entry| entry.path == parent)
+                {
+                    dirs.push(parent.to_string());
+                }
+            }
+        }
+        entries.extend(dirs.into_iter().map(|path| ArchiveEntry {
+            path,
+            kind: "dir",
+            size: 0,
+            modified: None,
+        }));
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(ArchiveShell {
+            path: "/".to_string(),
+            archive,
+            kind,
+            entries,
+            cwd: cwd.into(),
+            dirs: DirStack::default(),
+        })
+    }
+
+    fn find(&self, path: &str) -> Option<&ArchiveEntry> {
+        self.entries.iter().find(|entry| entry.path == path)
+    }
+
+    fn is_dir(&self, path: &str) -> bool {
+        path.is_empty() || self.find(path).is_some_and(|entry| entry.kind == "dir")
+    }
+
+    /// The entries matching a path, which may end with a glob pattern. A directory stands for
+    /// its children when listing.
+    fn matching(&self, target: &str) -> Result<Vec<&ArchiveEntry>, ShellError> {
+        let path = resolve(&self.path, target).join("/");
+        if self.is_dir(&path) {
+            return Ok(self.children(&path));
+        }
+        if let Some(entry) = self.find(&path) {
+            return Ok(vec![entry]);
+        }
+        let pattern = glob::Pattern::new(&path).map_err(|e| {
+            ShellError::new(
+                ErrorKind::InvalidInput,
+                format!("Invalid pattern {}: {}", target, e.msg),
+            )
+        })?;
+        let options = glob::MatchOptions {
+            require_literal_separator: true,
+            ..Default::default()
+        };
+        let matching: Vec<_> = self
+            .entries
+            .iter()
+            .filter(|entry| pattern.matches_with(&entry.path, options))
+            .collect();
+        if matching.is_empty() {
+            Err(ShellError::new(
+                ErrorKind::NotFound,
+                format!("No such file or pattern in the archive: {}", target),
+            ))
+        } else {
+            Ok(matching)
+        }
+    }
+
+    fn children(&self, dir: &str) -> Vec<&ArchiveEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| parent(&entry.path).unwrap_or_default() == dir)
+            .collect()
+    }
+
+    /// Extracts the files for which `target` gives a destination
+    fn extract(&self, target: impl Fn(&str) -> Option<PathBuf>) -> Result<usize, ShellError> {
+        let mut count = 0;
+        let mut write = |path: &str, reader: &mut dyn Read| -> Result<(), ShellError> {
+            if let Some(dst) = target(path) {
+                if let Some(dir) = dst.parent() {
+                    std::fs::create_dir_all(dir).map_err(|e| ShellError::io(e, dir))?;
+                }
+                let mut file = File::create(&dst).map_err(|e| ShellError::io(e, &dst))?;
+                io::copy(reader, &mut file).map_err(|e| ShellError::io(e, &dst))?;
+                count += 1;
+            }
+            Ok(())
+        };
+        match self.kind {
+            ArchiveKind::Tar | ArchiveKind::TarGz => {
+                let mut tar = tar::Archive::new(tar_reader(&self.archive, self.kind)?);
+                for entry in tar
+                    .entries()
+                    .map_err(|e| ShellError::io(e, &self.archive))?
+                {
+                    let mut entry = entry.map_err(|e| ShellError::io(e, &self.archive))?;
+                    if !entry.header().entry_type().is_file() {
+                        continue;
+                    }
+                    if let Some(path) = entry.path().ok().and_then(|path| normalize(&path)) {
+                        write(&path, &mut entry)?;
+                    }
+                }
+            }
+            ArchiveKind::Zip => {
+                let mut zip = open_zip(&self.archive)?;
+                for i in 0..zip.len() {
+                    let mut file = zip.by_index(i).map_err(|e| zip_error(e, &self.archive))?;
+                    if file.is_dir() {
+                        continue;
+                    }
+                    if let Some(path) = normalize(Path::new(file.name())) {
+                        write(&path, &mut file)?;
+                    }
+                }
+            }
+        }
+        Ok(count)
+    }
+}
+
+impl super::Shell for ArchiveShell {
+    fn name(&self) -> String {
+        format!("archive ({})", self.archive.display())
+    }
+
+    fn homedir(&self) -> Option<PathBuf> {
+        Some(PathBuf::from("/"))
+    }
+
+    fn ls(
+        &self,
+        LsArgs { path }: LsArgs,
+        _context: &RunnableContext,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        let target = path
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let current = resolve(&self.path, "").join("/");
+        let entries = self.matching(&target)?;
+        if entries.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(
+            entries
+                .into_iter()
+                .map(|entry| {
+                    // names are relative to the current directory, like in the file system
+                    let name = if current.is_empty() {
+                        entry.path.as_str()
+                    } else {
+                        entry
+                            .path
+                            .strip_prefix(&format!("{}/", current))
+                            .unwrap_or(&entry.path)
+                    };
+                    let mut row = Dictionary::default();
+                    row.insert("name", Value::String(name.to_string()));
+                    row.insert("type", Value::String(entry.kind.to_string()));
+                    row.insert("size", Value::Int(BigInt::from(entry.size)));
+                    row.insert(
+                        "modified",
+                        entry.modified.map_or(Value::Nothing, Value::Date),
+                    );
+                    Value::Row(row)
+                })
+                .collect(),
+        ))
+    }
+
+    fn cd(&mut self, args: CdArgs) -> Result<Option<Vec<Value>>, ShellError> {
+        let target = args
+            .dst
+            .map(|dst| dst.to_string_lossy().to_string())
+            .unwrap_or_else(|| "/".to_string());
+        let members = resolve(&self.path, &target);
+        if self.is_dir(&members.join("/")) {
+            self.set_path(join(&members));
+            Ok(None)
+        } else if self.find(&members.join("/")).is_some() {
+            Err(ShellError::new(
+                ErrorKind::InvalidInput,
+                format!("{} is not a directory", target),
+            ))
+        } else {
+            Err(ShellError::new(
+                ErrorKind::NotFound,
+                format!("No such directory in the archive: {}", target),
+            ))
+        }
+    }
+
+    /// Extracts files and directories, `src` is a path or pattern in the archive and `dst` a
+    /// path in the directory the shell was entered from
+    fn cp(&self, CopyArgs { src, dst }: CopyArgs) -> Result<Option<Vec<Value>>, ShellError> {
+        let src = src.to_string_lossy().to_string();
+        let path = resolve(&self.path, &src).join("/");
+        let roots: Vec<String> = match self.find(&path) {
+            _ if path.is_empty() => vec![String::new()],
+            Some(entry) => vec![entry.path.clone()],
+            None => self
+                .matching(&src)?
+                .into_iter()
+                .map(|entry| entry.path.clone())
+                .collect(),
+        };
+        let dst = self.cwd.join(dst);
+        let into_dir = dst.is_dir();
+        if roots.len() > 1 && !into_dir {
+            return Err(ShellError::new(
+                ErrorKind::InvalidInput,
+                format!("{} is not a directory", dst.display()),
+            )
+            .with_help("several files can only be extracted into a directory"));
+        }
+        let count = self.extract(|path| {
+            let root = roots.iter().find(|root| {
+                root.is_empty() || path == root.as_str() || path.starts_with(&format!("{}/", root))
+            })?;
+            if roots.len() == 1 && !into_dir {
+                // like `cp -r`, a single file or directory copied to a new name takes that name
+                return Some(if path == root {
+                    dst.clone()
+                } else {
+                    dst.join(relative(path, root))
+                });
+            }
+            Some(dst.join(relative(path, parent(root).unwrap_or_default())))
+        })?;
+        if count == 0 {
+            return Err(ShellError::new(
+                ErrorKind::NotFound,
+                format!("No files to extract at {}", src),
+            ));
+        }
+        Ok(None)
+    }
+
+    fn mkdir(&self, _args: MkdirArgs) -> Result<Option<Vec<Value>>, ShellError> {
+        Err(ShellError::new(
+            ErrorKind::PermissionDenied,
+            "Archives are read-only, use `cp` to extract files first",
+        ))
+    }
+
+    fn path(&self) -> String {
+        self.path.clone()
+    }
+
+    fn pwd(&self) -> Result<Option<Vec<Value>>, ShellError> {
+        Ok(Some(vec![Value::String(self.path())]))
+    }
+
+    fn set_path(&mut self, path: String) {
+        self.path = path;
+    }
+
+    fn cwd(&self) -> PathBuf {
+        self.cwd.clone()
+    }
+
+    fn dir_stack(&mut self) -> &mut DirStack {
+        &mut self.dirs
+    }
+}
+
+fn tar_reader(archive: &Path, kind: ArchiveKind) -> Result<Box<dyn Read>, ShellError> {
+    let file = File::open(archive).map_err(|e| ShellError::io(e, archive))?;
+    Ok(match kind {
+        ArchiveKind::TarGz => Box::new(flate2::read::GzDecoder::new(file)),
+        _ => Box::new(file),
+    })
+}
+
+fn open_zip(archive: &Path) -> Result<zip::ZipArchive<File>, ShellError> {
+    let file = File::open(archive).map_err(|e| ShellError::io(e, archive))?;
+    zip::ZipArchive::new(file).map_err(|e| zip_error(e, archive))
+}
+
+fn zip_error(err: zip::result::ZipError, archive: &Path) -> ShellError {
+    match err {
+        zip::result::ZipError::Io(e) => ShellError::io(e, archive),
+        err => ShellError::new(ErrorKind::Io, format!("{}: {}", archive.display(), err)),
+    }
+}
+
+/// The path of an entry with `/` separators and without leading `/` or `./`. Entries that would
+/// be extracted outside of the destination are left out.
+fn normalize(path: &Path) -> Option<String> {
+    let mut members = vec![];
+    for component in path.components() {
+        match component {
+            Component::Normal(member) => members.push(member.to_string_lossy().to_string()),
+            Component::CurDir | Component::RootDir | Component::Prefix(_) => {}
+            Component::ParentDir => return None,
+        }
+    }
+    if members.is_empty() {
+        None
+    } else {
+        Some(members.join("/"))
+    }
+}
+
+/// The path of an entry below a directory of the archive
+fn relative<'a>(path: &'a str, dir: &str) -> &'a str {
+    if dir.is_empty() {
+        path
+    } else {
+        &path[dir.len() + 1..]
+    }
+}
+
+fn parent(path: &str) -> Option<&str> {
+    path.rfind('/').map(|i| &path[..i])
+}
@@ -0,0 +1,50 @@
+/// How many directories `dirs --history` remembers
+const HISTORY_SIZE: usize = 100;
+
+/// The directories a shell has been in, for `cd -`, `pushd`, `popd` and `dirs`. The paths are
+/// those the shell shows with `pwd`.
+#[derive(Debug, Clone, Default)]
+pub struct DirStack {
+    previous: Option<String>,
+    stack: Vec<String>,
+    history: Vec<String>,
+}
+
+impl DirStack {
+    /// Records a change of directory
+    pub fn visited(&mut self, from: String, to: String) {
+        if self.history.is_empty() {
+            self.history.push(from.clone());
+        }
+        if self.history.last() != Some(&to) {
+            self.history.push(to);
+        }
+        if self.history.len() > HISTORY_SIZE {
+            self.history.remove(0);
+        }
+        self.previous = Some(from);
+    }
+
+    /// The directory `cd -` returns to
+    pub fn previous(&self) -> Option<&str> {
+        self.previous.as_deref()
+    }
+
+    pub fn push(&mut self, path: String) {
+        self.stack.push(path);
+    }
+
+    pub fn pop(&mut self) -> Option<String> {
+        self.stack.pop()
+    }
+
+    /// The directories pushed, the last pushed first
+    pub fn stack(&self) -> Vec<String> {
+        self.stack.iter().rev().cloned().collect()
+    }
+
+    /// The directories changed to, the oldest first
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+}
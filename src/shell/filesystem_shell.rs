@@ -1,22 +1,50 @@
-use std::env;
 use std::env::current_dir;
-use std::path::PathBuf;
+use std::fs::Metadata;
+use std::path::{Component, Path, PathBuf};
 use std::sync::atomic::Ordering;
 
+use chrono::{DateTime, Utc};
+use num_bigint::BigInt;
+
 use crate::commands::cd::CdArgs;
 use crate::commands::cp::CopyArgs;
 use crate::commands::ls::LsArgs;
 use crate::commands::mkdir::MkdirArgs;
 use crate::commands::RunnableContext;
-use crate::error::ShellError;
+use crate::error::{ErrorKind, ShellError};
+use crate::evaluate::dict::Dictionary;
 use crate::evaluate::Value;
+use crate::frecency;
+use crate::shell::{normalize, DirStack};
 
-#[derive(Debug, Clone, Default)]
-pub struct FilesystemShell {}
+#[derive(Debug, Clone)]
+pub struct FilesystemShell {
+    /// The working directory of the shell
+    path: String,
+    dirs: DirStack,
+}
 
 impl FilesystemShell {
+    /// A shell in the working directory of the process, or the home directory if that was removed
     pub fn new() -> Self {
-        Self::default()
+        let path = current_dir()
+            .ok()
+            .or_else(dirs::home_dir)
+            .unwrap_or_else(|| PathBuf::from("/"));
+        Self::with_location(path)
+    }
+
+    pub fn with_location(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into().to_string_lossy().to_string(),
+            dirs: DirStack::default(),
+        }
+    }
+}
+
+impl Default for FilesystemShell {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -35,17 +63,24 @@ impl super::Shell for FilesystemShell {
         context: &RunnableContext,
     ) -> Result<Option<Vec<Value>>, ShellError> {
         let ctrl_c = context.ctrl_c.clone();
+        let cwd = PathBuf::from(self.path());
         let path = match path {
+            None if !cwd.is_dir() => {
+                return Err(ShellError::new(
+                    ErrorKind::NotFound,
+                    format!("The current directory {} doesn't exist anymore", cwd.display()),
+                ));
+            }
             None => {
-                if is_dir_empty(&self.path().into()) {
+                if is_dir_empty(&cwd) {
                     return Ok(None);
                 } else {
-                    PathBuf::from("./*")
+                    PathBuf::from("*")
                 }
             }
             Some(mut p) => {
-                if p.is_dir() {
-                    if is_dir_empty(&p) {
+                if cwd.join(&p).is_dir() {
+                    if is_dir_empty(&cwd.join(&p)) {
                         return Ok(None);
                     }
                     p.push("*");
@@ -53,13 +88,19 @@ impl super::Shell for FilesystemShell {
                 p
             }
         };
-        let mut paths = match glob::glob(&path.to_string_lossy()) {
+        let mut paths = match glob::glob(&pattern_in(&cwd, &path)) {
             Ok(g) => Ok(g),
-            Err(_) => Err(ShellError::runtime_error("Invalid File or Pattern")),
+            Err(e) => Err(ShellError::new(
+                ErrorKind::InvalidInput,
+                format!("Invalid pattern {}: {}", path.display(), e.msg),
+            )),
         }?
         .peekable();
         if paths.peek().is_none() {
-            return Err(ShellError::runtime_error("Invalid File or Pattern"));
+            return Err(ShellError::new(
+                ErrorKind::NotFound,
+                format!("No such file or pattern: {}", path.display()),
+            ));
         }
         let mut results = vec![];
         for path in paths {
@@ -67,9 +108,9 @@ impl super::Shell for FilesystemShell {
                 break;
             }
             if let Ok(path) = path {
-                if let Some(name) = path.file_name().and_then(|s| s.to_str()) {
-                    let item = format!("{}: {}", get_path_type(&path), name);
-                    results.push(Value::String(item));
+                let name = path.strip_prefix(&cwd).unwrap_or(&path);
+                if let Some(entry) = dir_entry(&path, name) {
+                    results.push(entry);
                 }
             }
         }
@@ -80,36 +121,93 @@ impl super::Shell for FilesystemShell {
         }
     }
 
-    fn cd(&self, args: CdArgs) -> Result<Option<Vec<Value>>, ShellError> {
+    fn cd(&mut self, args: CdArgs) -> Result<Option<Vec<Value>>, ShellError> {
         let target = match args.dst {
             None => match dirs::home_dir() {
                 Some(o) => o,
                 _ => {
-                    return Err(ShellError::runtime_error(
+                    return Err(ShellError::new(
+                        ErrorKind::NotFound,
                         "Can not change to home directory",
                     ));
                 }
             },
             Some(target) => target,
         };
-        if target.exists() && !target.is_dir() {
-            return Err(ShellError::runtime_error(format!(
-                "{} is not a directory",
-                target.to_string_lossy().to_string()
-            )));
+        let path = PathBuf::from(self.path()).join(&target);
+        if path.exists() && !path.is_dir() {
+            return Err(ShellError::new(
+                ErrorKind::InvalidInput,
+                format!("{} is not a directory", target.to_string_lossy()),
+            ));
         }
-        let path = PathBuf::from(self.path());
-        match dunce::canonicalize(path.join(&target)) {
+        // `..` can't be followed out of a directory that was removed, resolve it by the name
+        let resolved = dunce::canonicalize(&path)
+            .or_else(|e| dunce::canonicalize(normalize(&path)).map_err(|_| e));
+        // a bare name that isn't a directory here jumps to the best match visited before, like
+        // `z`, and says where it went. A path like `./build` is only looked up here.
+        let is_bare_name = matches!(
+            target.components().collect::<Vec<_>>().as_slice(),
+            [Component::Normal(_)]
+        );
+        let resolved = resolved.or_else(|e| {
+            if !is_bare_name {
+                return Err(e);
+            }
+            let best = frecency::best(&[target.to_string_lossy().to_string()], &self.cwd());
+            let best = best.ok_or(e)?;
+            println!("{}", best.display());
+            Ok(best)
+        });
+        match resolved {
             Ok(p) => {
-                env::set_current_dir(p).expect("cannot to set current directory");
+                frecency::add(&p);
+                self.set_path(p.to_string_lossy().to_string());
                 Ok(None)
             }
-            Err(_) => Err(ShellError::runtime_error("directory not found")),
+            Err(e) => Err(ShellError::io(e, &target)),
         }
     }
 
-    fn cp(&self, _args: CopyArgs) -> Result<Option<Vec<Value>>, ShellError> {
-        unimplemented!()
+    fn cp(&self, CopyArgs { src, dst }: CopyArgs) -> Result<Option<Vec<Value>>, ShellError> {
+        let cwd = PathBuf::from(self.path());
+        let sources = glob::glob(&pattern_in(&cwd, &src))
+            .map_err(|e| {
+                ShellError::new(
+                    ErrorKind::InvalidInput,
+                    format!("Invalid pattern {}: {}", src.display(), e.msg),
+                )
+            })?
+            .filter_map(Result::ok)
+            .collect::<Vec<_>>();
+        if sources.is_empty() {
+            return Err(ShellError::new(
+                ErrorKind::NotFound,
+                format!("No such file or pattern: {}", src.display()),
+            ));
+        }
+        let dst = cwd.join(&dst);
+        if sources.len() > 1 && !dst.is_dir() {
+            return Err(ShellError::new(
+                ErrorKind::InvalidInput,
+                format!("{} is not a directory", dst.display()),
+            )
+            .with_help("several files can only be copied into a directory"));
+        }
+        for source in sources {
+            let target = match source.file_name() {
+                Some(name) if dst.is_dir() => dst.join(name),
+                _ => dst.clone(),
+            };
+            if target.starts_with(&source) {
+                return Err(ShellError::new(
+                    ErrorKind::InvalidInput,
+                    format!("Can't copy {} into itself", source.display()),
+                ));
+            }
+            copy_recursive(&source, &target)?;
+        }
+        Ok(None)
     }
 
     fn mkdir(
@@ -124,25 +222,62 @@ impl super::Shell for FilesystemShell {
                 loc
             };
 
-            let dir_res = std::fs::create_dir_all(create_at);
+            let dir_res = std::fs::create_dir_all(&create_at);
             if let Err(reason) = dir_res {
-                return Err(ShellError::runtime_error(reason.to_string()));
+                return Err(ShellError::io(reason, &create_at));
             }
         }
         Ok(None)
     }
 
     fn path(&self) -> String {
-        current_dir()
-            .expect("can't get current directory")
-            .to_string_lossy()
-            .to_string()
+        self.path.clone()
     }
     fn pwd(&self) -> Result<Option<Vec<Value>>, ShellError> {
         Ok(Some(vec![Value::String(self.path())]))
     }
+
+    fn set_path(&mut self, path: String) {
+        self.path = path;
+    }
+
+    fn cwd(&self) -> PathBuf {
+        PathBuf::from(&self.path)
+    }
+
+    fn dir_stack(&mut self) -> &mut DirStack {
+        &mut self.dirs
+    }
 }
 
+/// A glob pattern for a path relative to `cwd`, which may itself contain `*` or `[`
+fn pattern_in(cwd: &Path, path: &Path) -> String {
+    if path.is_absolute() {
+        path.to_string_lossy().to_string()
+    } else {
+        let mut prefix = glob::Pattern::escape(&cwd.to_string_lossy());
+        if !prefix.ends_with(std::path::MAIN_SEPARATOR) {
+            prefix.push(std::path::MAIN_SEPARATOR);
+        }
+        prefix + &path.to_string_lossy()
+    }
+}
+
+/// Copies a file, or a directory with everything in it
+fn copy_recursive(src: &Path, dst: &Path) -> Result<(), ShellError> {
+    if src.is_dir() {
+        std::fs::create_dir_all(dst).map_err(|e| ShellError::io(e, dst))?;
+        for entry in src.read_dir().map_err(|e| ShellError::io(e, src))? {
+            let entry = entry.map_err(|e| ShellError::io(e, src))?;
+            copy_recursive(&entry.path(), &dst.join(entry.file_name()))?;
+        }
+    } else {
+        std::fs::copy(src, dst).map_err(|e| ShellError::io(e, src))?;
+    }
+    Ok(())
+}
+
+/// Whether a directory has no entries, or can't be read
 fn is_dir_empty(d: &PathBuf) -> bool {
     match d.read_dir() {
         Err(_e) => true,
@@ -150,12 +285,31 @@ fn is_dir_empty(d: &PathBuf) -> bool {
     }
 }
 
-fn get_path_type(d: &PathBuf) -> &str {
-    if d.is_dir() {
-        "d"
-    } else if d.is_file() {
-        "f"
+fn get_path_type(metadata: &Metadata) -> &str {
+    if metadata.file_type().is_symlink() {
+        "symlink"
+    } else if metadata.is_dir() {
+        "dir"
+    } else if metadata.is_file() {
+        "file"
     } else {
-        " "
+        "unknown"
     }
 }
+
+/// Describes a directory entry as a row with its name, type, size and modification time
+fn dir_entry(path: &Path, name: &Path) -> Option<Value> {
+    let metadata = std::fs::symlink_metadata(path).ok()?;
+    let mut entry = Dictionary::default();
+    entry.insert("name", Value::String(name.to_string_lossy().to_string()));
+    entry.insert("type", Value::String(get_path_type(&metadata).to_string()));
+    entry.insert("size", Value::Int(BigInt::from(metadata.len())));
+    entry.insert(
+        "modified",
+        match metadata.modified() {
+            Ok(modified) => Value::Date(DateTime::<Utc>::from(modified)),
+            Err(_) => Value::Nothing,
+        },
+    );
+    Some(Value::Row(entry))
+}
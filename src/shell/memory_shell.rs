@@ -0,0 +1,620 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use num_bigint::BigInt;
+use parking_lot::Mutex;
+
+use crate::commands::cd::CdArgs;
+use crate::commands::cp::CopyArgs;
+use crate::commands::ls::LsArgs;
+use crate::commands::mkdir::MkdirArgs;
+use crate::commands::RunnableContext;
+use crate::error::{ErrorKind, ShellError};
+use crate::evaluate::dict::Dictionary;
+use crate::evaluate::Value;
+use crate::shell::{normalize, DirStack, Shell};
+
+#[derive(Debug, Clone)]
+enum Node {
+    Dir,
+    File(Vec<u8>),
+}
+
+/// What is known about a path without reading the file
+struct Stat {
+    is_dir: bool,
+    size: u64,
+    modified: Option<DateTime<Utc>>,
+}
+
+/// A shell over a file system kept in memory, for tests. In a dry run it lies over the real file
+/// system instead: what wasn't changed is read from the disk, and changes are kept in memory and
+/// printed. Clones share the files.
+#[derive(Debug, Clone)]
+pub struct MemoryShell {
+    path: PathBuf,
+    nodes: Arc<Mutex<BTreeMap<PathBuf, Node>>>,
+    dry_run: bool,
+    /// The changes printed in a dry run
+    reported: Arc<Mutex<Vec<String>>>,
+    dirs: DirStack,
+}
+
+impl MemoryShell {
+    /// A shell at the root of an empty file system
+    pub fn new() -> Self {
+        let mut nodes = BTreeMap::new();
+        nodes.insert(PathBuf::from("/"), Node::Dir);
+        Self {
+            path: PathBuf::from("/"),
+            nodes: Arc::new(Mutex::new(nodes)),
+            dry_run: false,
+            reported: Arc::new(Mutex::new(vec![])),
+            dirs: DirStack::default(),
+        }
+    }
+
+    /// A shell in the given directory of the real file system, which it doesn't change
+    pub fn dry_run(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            nodes: Arc::new(Mutex::new(BTreeMap::new())),
+            dry_run: true,
+            reported: Arc::new(Mutex::new(vec![])),
+            dirs: DirStack::default(),
+        }
+    }
+
+    /// Adds a directory and its parents, relative to the current path
+    pub fn add_dir(&self, path: impl AsRef<Path>) {
+        let path = self.resolve(path.as_ref());
+        self.create_dir_all(&path);
+    }
+
+    /// Adds a file and its parent directories, relative to the current path
+    pub fn add_file(&self, path: impl AsRef<Path>, contents: impl Into<Vec<u8>>) {
+        let path = self.resolve(path.as_ref());
+        if let Some(parent) = path.parent() {
+            self.create_dir_all(parent);
+        }
+        self.nodes.lock().insert(path, Node::File(contents.into()));
+    }
+
+    /// The changes a dry run would have made, in order
+    pub fn reported(&self) -> Vec<String> {
+        self.reported.lock().clone()
+    }
+
+    fn at(&self, path: PathBuf) -> Self {
+        Self {
+            path,
+            nodes: self.nodes.clone(),
+            dry_run: self.dry_run,
+            reported: self.reported.clone(),
+            dirs: DirStack::default(),
+        }
+    }
+
+    fn resolve(&self, path: &Path) -> PathBuf {
+        normalize(&self.path.join(path))
+    }
+
+    fn stat(&self, path: &Path) -> Option<Stat> {
+        match self.nodes.lock().get(path) {
+            Some(Node::Dir) => {
+                return Some(Stat {
+                    is_dir: true,
+                    size: 0,
+                    modified: None,
+                })
+            }
+            Some(Node::File(contents)) => {
+                return Some(Stat {
+                    is_dir: false,
+                    size: contents.len() as u64,
+                    modified: None,
+                })
+            }
+            None => {}
+        }
+        if !self.dry_run {
+            return None;
+        }
+        let metadata = std::fs::metadata(path).ok()?;
+        Some(Stat {
+            is_dir: metadata.is_dir(),
+            size: metadata.len(),
+            modified: metadata.modified().ok().map(DateTime::<Utc>::from),
+        })
+    }
+
+    fn children(&self, dir: &Path) -> Vec<PathBuf> {
+        let mut children = self
+            .nodes
+            .lock()
+            .keys()
+            .filter(|path| path.parent() == Some(dir))
+            .cloned()
+            .collect::<BTreeSet<_>>();
+        if self.dry_run {
+            if let Ok(entries) = std::fs::read_dir(dir) {
+                children.extend(entries.filter_map(Result::ok).map(|entry| entry.path()));
+            }
+        }
+        children.into_iter().collect()
+    }
+
+    /// The paths a name stands for, the last component may be a pattern
+    fn expand(&self, path: &Path) -> Result<Vec<PathBuf>, ShellError> {
+        let resolved = self.resolve(path);
+        if self.stat(&resolved).is_some() {
+            return Ok(vec![resolved]);
+        }
+        let (parent, name) = match (resolved.parent(), resolved.file_name()) {
+            (Some(parent), Some(name)) => (parent, name.to_string_lossy()),
+            _ => return Ok(vec![]),
+        };
+        let pattern = glob::Pattern::new(&name).map_err(|e| {
+            ShellError::new(
+                ErrorKind::InvalidInput,
+                format!("Invalid pattern {}: {}", path.display(), e.msg),
+            )
+        })?;
+        Ok(self
+            .children(parent)
+            .into_iter()
+            .filter(|child| match child.file_name() {
+                Some(name) => pattern.matches(&name.to_string_lossy()),
+                None => false,
+            })
+            .collect())
+    }
+
+    fn read(&self, path: &Path) -> Result<Vec<u8>, ShellError> {
+        match self.nodes.lock().get(path) {
+            Some(Node::File(contents)) => return Ok(contents.clone()),
+            Some(Node::Dir) => {
+                return Err(ShellError::new(
+                    ErrorKind::InvalidInput,
+                    format!("{} is a directory", path.display()),
+                ))
+            }
+            None => {}
+        }
+        if self.dry_run {
+            std::fs::read(path).map_err(|e| ShellError::io(e, path))
+        } else {
+            Err(not_found(path))
+        }
+    }
+
+    /// Checks that a file can be written at the path
+    fn check_writable(&self, path: &Path) -> Result<(), ShellError> {
+        match path.parent().map(|parent| self.stat(parent)) {
+            Some(Some(Stat { is_dir: true, .. })) => {}
+            _ => return Err(not_found(path)),
+        }
+        match self.stat(path) {
+            Some(Stat { is_dir: true, .. }) => Err(ShellError::new(
+                ErrorKind::InvalidInput,
+                format!("{} is a directory", path.display()),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn create_dir_all(&self, path: &Path) {
+        for ancestor in path.ancestors() {
+            if self.stat(ancestor).is_some() {
+                break;
+            }
+            self.nodes.lock().insert(ancestor.to_path_buf(), Node::Dir);
+        }
+    }
+
+    fn copy_recursive(&self, src: &Path, dst: &Path) -> Result<(), ShellError> {
+        match self.stat(src) {
+            Some(Stat { is_dir: true, .. }) => {
+                self.create_dir_all(dst);
+                for child in self.children(src) {
+                    if let Some(name) = child.file_name() {
+                        self.copy_recursive(&child, &dst.join(name))?;
+                    }
+                }
+                Ok(())
+            }
+            Some(_) => {
+                self.check_writable(dst)?;
+                let contents = self.read(src)?;
+                self.nodes
+                    .lock()
+                    .insert(dst.to_path_buf(), Node::File(contents));
+                Ok(())
+            }
+            None => Err(not_found(src)),
+        }
+    }
+
+    fn report(&self, action: String) {
+        if self.dry_run {
+            println!("dry run: would {}", action);
+            self.reported.lock().push(action);
+        }
+    }
+}
+
+impl Default for MemoryShell {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Shell for MemoryShell {
+    fn name(&self) -> String {
+        if self.dry_run {
+            "dry run".to_string()
+        } else {
+            "memory".to_string()
+        }
+    }
+
+    fn homedir(&self) -> Option<PathBuf> {
+        if self.dry_run {
+            dirs::home_dir()
+        } else {
+            Some(PathBuf::from("/"))
+        }
+    }
+
+    fn ls(
+        &self,
+        LsArgs { path }: LsArgs,
+        context: &RunnableContext,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        let paths = match path {
+            None => self.children(&self.path),
+            Some(path) => match self.stat(&self.resolve(&path)) {
+                Some(Stat { is_dir: true, .. }) => self.children(&self.resolve(&path)),
+                _ => {
+                    let paths = self.expand(&path)?;
+                    if paths.is_empty() {
+                        return Err(ShellError::new(
+                            ErrorKind::NotFound,
+                            format!("No such file or pattern: {}", path.display()),
+                        ));
+                    }
+                    paths
+                }
+            },
+        };
+        let mut results = vec![];
+        for path in paths {
+            if context.ctrl_c.load(Ordering::SeqCst) {
+                break;
+            }
+            let stat = match self.stat(&path) {
+                Some(stat) => stat,
+                None => continue,
+            };
+            let name = path.strip_prefix(&self.path).unwrap_or(&path);
+            let mut entry = Dictionary::default();
+            entry.insert("name", Value::String(name.to_string_lossy().to_string()));
+            entry.insert(
+                "type",
+                Value::String(if stat.is_dir { "dir" } else { "file" }.to_string()),
+            );
+            entry.insert("size", Value::Int(BigInt::from(stat.size)));
+            entry.insert(
+                "modified",
+                stat.modified.map(Value::Date).unwrap_or(Value::Nothing),
+            );
+            results.push(Value::Row(entry));
+        }
+        if results.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(results))
+        }
+    }
+
+    fn cd(&mut self, args: CdArgs) -> Result<Option<Vec<Value>>, ShellError> {
+        let target = match args.dst.or_else(|| self.homedir()) {
+            Some(target) => target,
+            None => {
+                return Err(ShellError::new(
+                    ErrorKind::NotFound,
+                    "Can not change to home directory",
+                ))
+            }
+        };
+        let path = self.resolve(&target);
+        match self.stat(&path) {
+            Some(Stat { is_dir: true, .. }) => {
+                self.path = path;
+                Ok(None)
+            }
+            Some(_) => Err(ShellError::new(
+                ErrorKind::InvalidInput,
+                format!("{} is not a directory", target.display()),
+            )),
+            None => Err(not_found(&target)),
+        }
+    }
+
+    fn cp(&self, CopyArgs { src, dst }: CopyArgs) -> Result<Option<Vec<Value>>, ShellError> {
+        let sources = self.expand(&src)?;
+        if sources.is_empty() {
+            return Err(ShellError::new(
+                ErrorKind::NotFound,
+                format!("No such file or pattern: {}", src.display()),
+            ));
+        }
+        let dst = self.resolve(&dst);
+        let dst_is_dir = match self.stat(&dst) {
+            Some(stat) => stat.is_dir,
+            None => false,
+        };
+        if sources.len() > 1 && !dst_is_dir {
+            return Err(ShellError::new(
+                ErrorKind::InvalidInput,
+                format!("{} is not a directory", dst.display()),
+            )
+            .with_help("several files can only be copied into a directory"));
+        }
+        for source in sources {
+            let target = match source.file_name() {
+                Some(name) if dst_is_dir => dst.join(name),
+                _ => dst.clone(),
+            };
+            if target.starts_with(&source) {
+                return Err(ShellError::new(
+                    ErrorKind::InvalidInput,
+                    format!("Can't copy {} into itself", source.display()),
+                ));
+            }
+            self.copy_recursive(&source, &target)?;
+            self.report(format!("copy {} to {}", source.display(), target.display()));
+        }
+        Ok(None)
+    }
+
+    fn mkdir(
+        &self,
+        MkdirArgs { rest: directories }: MkdirArgs,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        for dir in directories {
+            let path = self.resolve(&dir);
+            match self.stat(&path) {
+                Some(Stat { is_dir: true, .. }) => continue,
+                Some(_) => {
+                    return Err(ShellError::new(
+                        ErrorKind::AlreadyExists,
+                        format!("{} already exists and is not a directory", dir.display()),
+                    ))
+                }
+                None => {}
+            }
+            self.create_dir_all(&path);
+            self.report(format!("create directory {}", path.display()));
+        }
+        Ok(None)
+    }
+
+    fn path(&self) -> String {
+        self.path.to_string_lossy().to_string()
+    }
+
+    fn pwd(&self) -> Result<Option<Vec<Value>>, ShellError> {
+        Ok(Some(vec![Value::String(self.path())]))
+    }
+
+    fn set_path(&mut self, path: String) {
+        self.path = PathBuf::from(path);
+    }
+
+    fn cwd(&self) -> PathBuf {
+        self.path.clone()
+    }
+
+    fn dir_stack(&mut self) -> &mut DirStack {
+        &mut self.dirs
+    }
+
+    fn enter_dir(&self, path: &Path) -> Option<Box<dyn Shell>> {
+        let path = self.resolve(path);
+        match self.stat(&path) {
+            Some(Stat { is_dir: true, .. }) => Some(Box::new(self.at(path))),
+            _ => None,
+        }
+    }
+
+    fn read_file(&self, path: &Path) -> Result<String, ShellError> {
+        let path = self.resolve(path);
+        let contents = self.read(&path)?;
+        String::from_utf8(contents).map_err(|_| {
+            ShellError::new(
+                ErrorKind::InvalidInput,
+                format!("{} is not text", path.display()),
+            )
+        })
+    }
+
+    fn write_file(&self, path: &Path, contents: &str) -> Result<(), ShellError> {
+        let path = self.resolve(path);
+        self.check_writable(&path)?;
+        self.nodes
+            .lock()
+            .insert(path.clone(), Node::File(contents.as_bytes().to_vec()));
+        self.report(format!(
+            "write {} bytes to {}",
+            contents.len(),
+            path.display()
+        ));
+        Ok(())
+    }
+}
+
+fn not_found(path: &Path) -> ShellError {
+    ShellError::new(
+        ErrorKind::NotFound,
+        format!("No such file or directory: {}", path.display()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::MemoryShell;
+    use crate::cli::{classify_pipeline, create_default_context};
+    use crate::commands::classified::pipeline::run_pipeline;
+    use crate::context::Context;
+    use crate::error::{ErrorKind, ShellError};
+    use crate::evaluate::Value;
+    use crate::parser;
+    use crate::shell::ShellManager;
+
+    fn context(shell: &MemoryShell) -> Context {
+        let mut context = create_default_context();
+        context.shell = ShellManager::new(Box::new(shell.clone()));
+        context
+    }
+
+    fn run(context: &mut Context, line: &str) -> Result<Vec<Value>, ShellError> {
+        let pipeline = parser::parse(line)?;
+        let pipeline = classify_pipeline(&pipeline, context, line)?;
+        Ok(run_pipeline(pipeline, context, None)?.unwrap_or_default())
+    }
+
+    /// The names listed by `ls`, with `/` after directories
+    fn ls(context: &mut Context, line: &str) -> Vec<String> {
+        run(context, line)
+            .unwrap()
+            .iter()
+            .map(|row| {
+                let name = row.get_data_by_column_path("name").unwrap().to_string();
+                match row.get_data_by_column_path("type") {
+                    Some(Value::String(kind)) if kind == "dir" => format!("{}/", name),
+                    _ => name,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn mkdir_creates_directories_and_their_parents() {
+        let shell = MemoryShell::new();
+        let mut context = context(&shell);
+        run(&mut context, "mkdir a/b c").unwrap();
+        assert_eq!(ls(&mut context, "ls"), vec!["a/", "c/"]);
+        assert_eq!(ls(&mut context, "ls a"), vec!["a/b/"]);
+
+        shell.add_file("file", "");
+        let err = run(&mut context, "mkdir file").unwrap_err();
+        assert_eq!(err.kind(), Some(ErrorKind::AlreadyExists));
+    }
+
+    #[test]
+    fn ls_lists_files_and_patterns() {
+        let shell = MemoryShell::new();
+        shell.add_file("src/main.rs", "fn main() {}");
+        shell.add_file("src/lib.rs", "");
+        shell.add_file("README.md", "# shell");
+        let mut context = context(&shell);
+        assert_eq!(ls(&mut context, "ls"), vec!["README.md", "src/"]);
+        assert_eq!(
+            ls(&mut context, "ls src/*.rs"),
+            vec!["src/lib.rs", "src/main.rs"]
+        );
+
+        let rows = run(&mut context, "ls README.md").unwrap();
+        let size = rows[0].get_data_by_column_path("size").unwrap();
+        assert_eq!(size.to_string(), "7");
+
+        let err = run(&mut context, "ls *.txt").unwrap_err();
+        assert_eq!(err.kind(), Some(ErrorKind::NotFound));
+    }
+
+    #[test]
+    fn cd_moves_between_directories() {
+        let shell = MemoryShell::new();
+        shell.add_dir("src/commands");
+        shell.add_file("src/lib.rs", "");
+        let mut context = context(&shell);
+        run(&mut context, "cd src/commands").unwrap();
+        assert_eq!(context.shell.path(), "/src/commands");
+        run(&mut context, "cd ..").unwrap();
+        assert_eq!(ls(&mut context, "ls"), vec!["commands/", "lib.rs"]);
+        run(&mut context, "cd -").unwrap();
+        assert_eq!(context.shell.path(), "/src/commands");
+
+        let err = run(&mut context, "cd missing").unwrap_err();
+        assert_eq!(err.kind(), Some(ErrorKind::NotFound));
+        let err = run(&mut context, "cd ../lib.rs").unwrap_err();
+        assert_eq!(err.kind(), Some(ErrorKind::InvalidInput));
+        assert_eq!(context.shell.path(), "/src/commands");
+    }
+
+    #[test]
+    fn cp_copies_files_and_directories() {
+        let shell = MemoryShell::new();
+        shell.add_file("src/main.rs", "fn main() {}");
+        shell.add_file("src/lib.rs", "pub mod cli;");
+        let mut context = context(&shell);
+        run(&mut context, "cp src/main.rs main.rs").unwrap();
+        assert_eq!(
+            context.shell.read_file(Path::new("main.rs")).unwrap(),
+            "fn main() {}"
+        );
+
+        run(&mut context, "mkdir backup").unwrap();
+        run(&mut context, "cp src/*.rs backup").unwrap();
+        assert_eq!(
+            ls(&mut context, "ls backup"),
+            vec!["backup/lib.rs", "backup/main.rs"]
+        );
+
+        run(&mut context, "cp src copy").unwrap();
+        assert_eq!(
+            ls(&mut context, "ls copy"),
+            vec!["copy/lib.rs", "copy/main.rs"]
+        );
+
+        let err = run(&mut context, "cp src src/inner").unwrap_err();
+        assert_eq!(err.kind(), Some(ErrorKind::InvalidInput));
+        let err = run(&mut context, "cp src/*.rs main.rs").unwrap_err();
+        assert_eq!(err.kind(), Some(ErrorKind::InvalidInput));
+    }
+
+    #[test]
+    fn dry_run_reports_changes_without_making_them() {
+        let dir = std::env::temp_dir().join(format!("shell-dry-run-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("notes.txt"), "notes").unwrap();
+        let shell = MemoryShell::dry_run(&dir);
+        let mut context = context(&shell);
+
+        run(&mut context, "mkdir build").unwrap();
+        run(&mut context, "cp notes.txt build").unwrap();
+        assert_eq!(ls(&mut context, "ls"), vec!["build/", "notes.txt"]);
+        assert_eq!(ls(&mut context, "ls build"), vec!["build/notes.txt"]);
+        assert!(!dir.join("build").exists());
+
+        let target = PathBuf::from("build").join("notes.txt");
+        assert_eq!(
+            shell.reported(),
+            vec![
+                format!("create directory {}", dir.join("build").display()),
+                format!(
+                    "copy {} to {}",
+                    dir.join("notes.txt").display(),
+                    dir.join(target).display()
+                ),
+            ]
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,6 +1,11 @@
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 
+pub use archive_shell::{ArchiveKind, ArchiveShell};
+pub use dir_stack::DirStack;
 pub use filesystem_shell::FilesystemShell;
+pub use memory_shell::MemoryShell;
+pub use shell_manager::ShellManager;
+pub use value_shell::ValueShell;
 
 use crate::commands::cd::CdArgs;
 use crate::commands::cp::CopyArgs;
@@ -10,20 +15,98 @@ use crate::commands::RunnableContext;
 use crate::error::ShellError;
 use crate::evaluate::Value;
 
+pub mod archive_shell;
+pub mod dir_stack;
 pub mod filesystem_shell;
+pub mod memory_shell;
+pub mod shell_manager;
+pub mod value_shell;
 
-pub trait Shell: std::fmt::Debug {
+pub trait Shell: std::fmt::Debug + Send + Sync {
     fn name(&self) -> String;
     fn homedir(&self) -> Option<PathBuf>;
 
     fn ls(&self, args: LsArgs, context: &RunnableContext)
         -> Result<Option<Vec<Value>>, ShellError>;
-    fn cd(&self, args: CdArgs) -> Result<Option<Vec<Value>>, ShellError>;
+    fn cd(&mut self, args: CdArgs) -> Result<Option<Vec<Value>>, ShellError>;
     fn cp(&self, args: CopyArgs) -> Result<Option<Vec<Value>>, ShellError>;
     fn mkdir(&self, args: MkdirArgs) -> Result<Option<Vec<Value>>, ShellError>;
     //    fn mv(&self, args: MoveArgs, name: Tag, path: &str) -> Result<OutputStream, ShellError>;
     //    fn rm(&self, args: RemoveArgs, name: Tag, path: &str) -> Result<OutputStream, ShellError>;
     fn path(&self) -> String;
     fn pwd(&self) -> Result<Option<Vec<Value>>, ShellError>;
-    //    fn set_path(&mut self, path: String);
+    fn set_path(&mut self, path: String);
+    /// The directory relative file names are resolved against, for a shell that isn't over the
+    /// file system it is the directory it was entered from
+    fn cwd(&self) -> PathBuf;
+
+    /// The directories the shell has been in
+    fn dir_stack(&mut self) -> &mut DirStack;
+
+    /// The data of a shell over a value
+    fn value(&self) -> Option<Value> {
+        None
+    }
+
+    /// The file the data of the shell was loaded from
+    fn source(&self) -> Option<PathBuf> {
+        None
+    }
+
+    /// The shell `enter` starts for a directory, or None if the path isn't one
+    fn enter_dir(&self, path: &Path) -> Option<Box<dyn Shell>> {
+        let path = dunce::canonicalize(path).ok().filter(|path| path.is_dir())?;
+        Some(Box::new(FilesystemShell::with_location(path)))
+    }
+
+    /// Reads the text of a file, for commands like `open` and `cat`
+    fn read_file(&self, path: &Path) -> Result<String, ShellError> {
+        std::fs::read_to_string(path).map_err(|e| ShellError::io(e, path))
+    }
+
+    /// Writes the text of a file, for commands like `save`
+    fn write_file(&self, path: &Path, contents: &str) -> Result<(), ShellError> {
+        std::fs::write(path, contents).map_err(|e| ShellError::io(e, path))
+    }
+}
+
+/// Resolves a path against the current path of a shell that isn't over the file system, where
+/// paths are separated by `/`. `..` goes up and a leading `/` starts at the root.
+pub(crate) fn resolve(current: &str, target: &str) -> Vec<String> {
+    let mut members = vec![];
+    let target = if target.starts_with('/') {
+        target.to_string()
+    } else {
+        format!("{}/{}", current, target)
+    };
+    for member in target.split('/') {
+        match member {
+            "" | "." => {}
+            ".." => {
+                members.pop();
+            }
+            member => members.push(member.to_string()),
+        }
+    }
+    members
+}
+
+/// The absolute path of the given members
+pub(crate) fn join(members: &[String]) -> String {
+    format!("/{}", members.join("/"))
+}
+
+/// Resolves `.` and `..` in a path by its names alone, without looking at the file system
+pub(crate) fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
 }
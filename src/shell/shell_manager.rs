@@ -0,0 +1,249 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use crate::commands::cd::CdArgs;
+use crate::commands::cp::CopyArgs;
+use crate::commands::ls::LsArgs;
+use crate::commands::mkdir::MkdirArgs;
+use crate::commands::RunnableContext;
+use crate::error::{ErrorKind, ShellError};
+use crate::evaluate::Value;
+use crate::shell::{FilesystemShell, Shell};
+
+/// The stack of shells entered with `enter`, the commands act on the current one. Clones share
+/// the stack like the other parts of the context.
+#[derive(Debug, Clone)]
+pub struct ShellManager {
+    current: Arc<AtomicUsize>,
+    shells: Arc<Mutex<Vec<Box<dyn Shell>>>>,
+}
+
+impl ShellManager {
+    pub fn basic() -> ShellManager {
+        ShellManager::new(Box::new(FilesystemShell::new()))
+    }
+
+    /// A stack of only the given shell
+    pub fn new(shell: Box<dyn Shell>) -> ShellManager {
+        ShellManager {
+            current: Arc::new(AtomicUsize::new(0)),
+            shells: Arc::new(Mutex::new(vec![shell])),
+        }
+    }
+
+    /// Adds a shell after the current one and switches to it
+    pub fn insert_at_current(&self, shell: Box<dyn Shell>) {
+        let mut shells = self.shells.lock();
+        let index = (self.current.load(Ordering::SeqCst) + 1).min(shells.len());
+        shells.insert(index, shell);
+        self.current.store(index, Ordering::SeqCst);
+    }
+
+    /// Removes the current shell and switches to the one before it. Returns false if it was the
+    /// last one.
+    pub fn remove_at_current(&self) -> bool {
+        let mut shells = self.shells.lock();
+        if shells.len() <= 1 {
+            return false;
+        }
+        let index = self.current.load(Ordering::SeqCst);
+        shells.remove(index);
+        self.current
+            .store(index.saturating_sub(1), Ordering::SeqCst);
+        true
+    }
+
+    /// Switches to the next shell, the first one after the last
+    pub fn next(&self) {
+        let len = self.len();
+        let index = self.current.load(Ordering::SeqCst);
+        self.current.store((index + 1) % len, Ordering::SeqCst);
+    }
+
+    /// Switches to the previous shell, the last one before the first
+    pub fn prev(&self) {
+        let len = self.len();
+        let index = self.current.load(Ordering::SeqCst);
+        self.current
+            .store((index + len - 1) % len, Ordering::SeqCst);
+    }
+
+    pub fn len(&self) -> usize {
+        self.shells.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn current_index(&self) -> usize {
+        self.current.load(Ordering::SeqCst)
+    }
+
+    /// The name and path of every shell
+    pub fn list(&self) -> Vec<(String, String)> {
+        self.shells
+            .lock()
+            .iter()
+            .map(|shell| (shell.name(), shell.path()))
+            .collect()
+    }
+
+    fn with_current<T>(&self, f: impl FnOnce(&mut dyn Shell) -> T) -> T {
+        let mut shells = self.shells.lock();
+        let index = self.current.load(Ordering::SeqCst);
+        f(shells[index].as_mut())
+    }
+
+    pub fn name(&self) -> String {
+        self.with_current(|shell| shell.name())
+    }
+
+    pub fn homedir(&self) -> Option<PathBuf> {
+        self.with_current(|shell| shell.homedir())
+    }
+
+    pub fn ls(
+        &self,
+        args: LsArgs,
+        context: &RunnableContext,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        self.with_current(|shell| shell.ls(args, context))
+    }
+
+    /// Changes the directory of the current shell, `-` changes back to the previous one
+    pub fn cd(&self, CdArgs { dst }: CdArgs) -> Result<Option<Vec<Value>>, ShellError> {
+        self.with_current(|shell| {
+            let dst = match dst {
+                Some(dst) if dst == Path::new("-") => match shell.dir_stack().previous() {
+                    Some(previous) => Some(PathBuf::from(previous)),
+                    None => {
+                        return Err(ShellError::new(
+                            ErrorKind::NotFound,
+                            "There is no previous directory to change to",
+                        ))
+                    }
+                },
+                dst => dst,
+            };
+            change_dir(shell, dst)
+        })
+    }
+
+    /// Changes to a directory and pushes the one left on the stack. Without a directory it swaps
+    /// the current directory with the top of the stack.
+    pub fn pushd(&self, dst: Option<PathBuf>) -> Result<(), ShellError> {
+        self.with_current(|shell| {
+            let from = shell.path();
+            match dst {
+                Some(dst) => {
+                    change_dir(shell, Some(dst))?;
+                }
+                None => {
+                    let top = shell.dir_stack().pop().ok_or_else(empty_stack)?;
+                    if let Err(err) = change_dir(shell, Some(PathBuf::from(&top))) {
+                        shell.dir_stack().push(top);
+                        return Err(err);
+                    }
+                }
+            }
+            shell.dir_stack().push(from);
+            Ok(())
+        })
+    }
+
+    /// Changes to the directory on the top of the stack and removes it
+    pub fn popd(&self) -> Result<(), ShellError> {
+        self.with_current(|shell| {
+            let top = shell.dir_stack().pop().ok_or_else(empty_stack)?;
+            if let Err(err) = change_dir(shell, Some(PathBuf::from(&top))) {
+                shell.dir_stack().push(top);
+                return Err(err);
+            }
+            Ok(())
+        })
+    }
+
+    /// The current directory followed by the stack, the last pushed first
+    pub fn dirs(&self) -> Vec<String> {
+        self.with_current(|shell| {
+            let mut dirs = vec![shell.path()];
+            dirs.extend(shell.dir_stack().stack());
+            dirs
+        })
+    }
+
+    /// The directories the current shell changed to, the oldest first
+    pub fn dir_history(&self) -> Vec<String> {
+        self.with_current(|shell| match shell.dir_stack().history() {
+            [] => vec![shell.path()],
+            history => history.to_vec(),
+        })
+    }
+
+    pub fn cp(&self, args: CopyArgs) -> Result<Option<Vec<Value>>, ShellError> {
+        self.with_current(|shell| shell.cp(args))
+    }
+
+    pub fn mkdir(&self, args: MkdirArgs) -> Result<Option<Vec<Value>>, ShellError> {
+        self.with_current(|shell| shell.mkdir(args))
+    }
+
+    pub fn path(&self) -> String {
+        self.with_current(|shell| shell.path())
+    }
+
+    pub fn set_path(&self, path: String) {
+        self.with_current(|shell| shell.set_path(path))
+    }
+
+    pub fn cwd(&self) -> PathBuf {
+        self.with_current(|shell| shell.cwd())
+    }
+
+    pub fn value(&self) -> Option<Value> {
+        self.with_current(|shell| shell.value())
+    }
+
+    pub fn source(&self) -> Option<PathBuf> {
+        self.with_current(|shell| shell.source())
+    }
+
+    pub fn enter_dir(&self, path: &Path) -> Option<Box<dyn Shell>> {
+        self.with_current(|shell| shell.enter_dir(path))
+    }
+
+    pub fn read_file(&self, path: &Path) -> Result<String, ShellError> {
+        self.with_current(|shell| shell.read_file(path))
+    }
+
+    pub fn write_file(&self, path: &Path, contents: &str) -> Result<(), ShellError> {
+        self.with_current(|shell| shell.write_file(path, contents))
+    }
+
+    pub fn pwd(&self) -> Result<Option<Vec<Value>>, ShellError> {
+        self.with_current(|shell| shell.pwd())
+    }
+}
+
+/// Changes the directory of a shell and records it in the history
+fn change_dir(
+    shell: &mut dyn Shell,
+    dst: Option<PathBuf>,
+) -> Result<Option<Vec<Value>>, ShellError> {
+    let from = shell.path();
+    let result = shell.cd(CdArgs { dst })?;
+    let to = shell.path();
+    if from != to {
+        shell.dir_stack().visited(from, to);
+    }
+    Ok(result)
+}
+
+fn empty_stack() -> ShellError {
+    ShellError::new(ErrorKind::NotFound, "The directory stack is empty")
+        .with_help("directories are pushed with `pushd <dir>`")
+}
@@ -0,0 +1,175 @@
+use std::path::PathBuf;
+
+use crate::commands::cd::CdArgs;
+use crate::commands::cp::CopyArgs;
+use crate::commands::ls::LsArgs;
+use crate::commands::mkdir::MkdirArgs;
+use crate::commands::RunnableContext;
+use crate::error::{ErrorKind, ShellError};
+use crate::evaluate::dict::Dictionary;
+use crate::evaluate::Value;
+use crate::shell::{join, resolve, DirStack};
+
+/// A shell over a value, eg a parsed JSON file, where rows and lists are the directories and
+/// their columns and items the entries. Paths look like `/dependencies/serde` or `/items/0`.
+#[derive(Debug, Clone)]
+pub struct ValueShell {
+    /// The path of the current value, always absolute
+    path: String,
+    value: Value,
+    /// The directory the shell was entered from, files are read and written there
+    cwd: PathBuf,
+    /// The file the value was loaded from, where it is saved back to
+    source: Option<PathBuf>,
+    dirs: DirStack,
+}
+
+impl ValueShell {
+    pub fn new(value: Value, cwd: impl Into<PathBuf>, source: Option<PathBuf>) -> Self {
+        Self {
+            path: "/".to_string(),
+            value,
+            cwd: cwd.into(),
+            source,
+            dirs: DirStack::default(),
+        }
+    }
+
+    /// The value at the given members, or an error naming the first one that doesn't exist
+    fn find(&self, members: &[String]) -> Result<&Value, ShellError> {
+        let mut current = &self.value;
+        for (i, member) in members.iter().enumerate() {
+            current = match member_of(current, member) {
+                Some(value) => value,
+                None => {
+                    return Err(ShellError::new(
+                        ErrorKind::NotFound,
+                        format!("No such path: {}", join(&members[..=i])),
+                    ))
+                }
+            };
+        }
+        Ok(current)
+    }
+}
+
+impl super::Shell for ValueShell {
+    fn name(&self) -> String {
+        match &self.source {
+            Some(source) => format!("value ({})", source.display()),
+            None => "value".to_string(),
+        }
+    }
+
+    fn homedir(&self) -> Option<PathBuf> {
+        Some(PathBuf::from("/"))
+    }
+
+    fn ls(
+        &self,
+        LsArgs { path }: LsArgs,
+        _context: &RunnableContext,
+    ) -> Result<Option<Vec<Value>>, ShellError> {
+        let members = match path {
+            Some(path) => resolve(&self.path, &path.to_string_lossy()),
+            None => resolve(&self.path, ""),
+        };
+        let entries: Vec<(String, &Value)> = match self.find(&members)? {
+            Value::Row(d) => d.iter().map(|(k, v)| (k.clone(), v)).collect(),
+            Value::List(l) => l
+                .iter()
+                .enumerate()
+                .map(|(i, v)| (i.to_string(), v))
+                .collect(),
+            value => return Ok(Some(vec![value.clone()])),
+        };
+        if entries.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(
+            entries
+                .into_iter()
+                .map(|(name, value)| {
+                    let mut entry = Dictionary::default();
+                    entry.insert("name", Value::String(name));
+                    entry.insert("type", Value::String(value.type_name().to_string()));
+                    entry.insert("value", value.clone());
+                    Value::Row(entry)
+                })
+                .collect(),
+        ))
+    }
+
+    fn cd(&mut self, args: CdArgs) -> Result<Option<Vec<Value>>, ShellError> {
+        let target = args
+            .dst
+            .map(|dst| dst.to_string_lossy().to_string())
+            .unwrap_or_else(|| "/".to_string());
+        let members = resolve(&self.path, &target);
+        match self.find(&members)? {
+            Value::Row(_) | Value::List(_) => {
+                self.set_path(join(&members));
+                Ok(None)
+            }
+            value => Err(ShellError::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Can't enter {}, it is of type {}",
+                    target,
+                    value.type_name()
+                ),
+            )
+            .with_help("only rows and lists can be entered")),
+        }
+    }
+
+    fn cp(&self, _args: CopyArgs) -> Result<Option<Vec<Value>>, ShellError> {
+        Err(ShellError::new(
+            ErrorKind::InvalidInput,
+            "cp is not supported in a value shell",
+        ))
+    }
+
+    fn mkdir(&self, _args: MkdirArgs) -> Result<Option<Vec<Value>>, ShellError> {
+        Err(ShellError::new(
+            ErrorKind::InvalidInput,
+            "mkdir is not supported in a value shell",
+        ))
+    }
+
+    fn path(&self) -> String {
+        self.path.clone()
+    }
+
+    fn pwd(&self) -> Result<Option<Vec<Value>>, ShellError> {
+        Ok(Some(vec![Value::String(self.path())]))
+    }
+
+    fn set_path(&mut self, path: String) {
+        self.path = path;
+    }
+
+    fn cwd(&self) -> PathBuf {
+        self.cwd.clone()
+    }
+
+    fn value(&self) -> Option<Value> {
+        Some(self.value.clone())
+    }
+
+    fn source(&self) -> Option<PathBuf> {
+        self.source.clone()
+    }
+
+    fn dir_stack(&mut self) -> &mut DirStack {
+        &mut self.dirs
+    }
+}
+
+fn member_of<'a>(value: &'a Value, member: &str) -> Option<&'a Value> {
+    match value {
+        Value::Row(d) => d.get(member),
+        Value::List(l) => l.get(member.parse::<usize>().ok()?),
+        _ => None,
+    }
+}
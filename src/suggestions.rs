@@ -0,0 +1,49 @@
+//! "Did you mean" suggestions for misspelled names
+
+use std::collections::HashSet;
+
+/// The number of insertions, deletions, substitutions and transpositions of single characters
+/// needed to turn `a` into `b`
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // rows i-2, i-1 and i of the distance matrix
+    let mut previous2: Vec<usize> = vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current: Vec<usize> = vec![0; b.len() + 1];
+    for i in 1..=a.len() {
+        current[0] = i;
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            current[j] = (previous[j] + 1)
+                .min(current[j - 1] + 1)
+                .min(previous[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(previous2[j - 2] + 1);
+            }
+        }
+        std::mem::swap(&mut previous2, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// The three candidates closest to `name`, best first, candidates as close keep their order.
+/// Candidates further away than a third of the length of the name aren't similar enough to be
+/// suggested.
+pub fn suggest(name: &str, candidates: impl IntoIterator<Item = String>) -> Vec<String> {
+    let max_distance = (name.chars().count() / 3).max(1);
+    let mut seen = HashSet::new();
+    let mut ranked: Vec<(usize, String)> = candidates
+        .into_iter()
+        .filter(|candidate| candidate != name && seen.insert(candidate.clone()))
+        .map(|candidate| (edit_distance(name, &candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    ranked.sort_by_key(|(distance, _)| *distance);
+    ranked
+        .into_iter()
+        .take(3)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}